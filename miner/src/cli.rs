@@ -0,0 +1,178 @@
+//! BitNice 独立矿工命令行参数
+//!
+//! `--threads`、`--coinbase`、`--verbose` 与节点 `mine` 子命令的含义相同
+
+use clap::{error::ErrorKind, CommandFactory, Parser};
+use sp_core::crypto::AccountId32;
+
+use crate::Error;
+
+/// 独立矿工命令行参数
+#[derive(Debug, clap::Parser)]
+#[command(name = "bitnice-miner", version, about = "BitNice 独立 SHA-256 矿工")]
+pub struct Cli {
+    /// 节点 RPC（`ws://`、`http://`）或 Stratum（`stratum+tcp://`）地址
+    #[arg(long, default_value = "ws://127.0.0.1:9944")]
+    pub url: String,
+
+    /// 挖矿线程数量，0 表示使用全部 CPU 核心
+    #[arg(long, default_value = "1")]
+    pub threads: usize,
+
+    /// 挖矿奖励接收地址，作为 Stratum 登录名的前缀；节点模式的奖励由节点 `mine --coinbase` 决定，不能指定
+    #[arg(long)]
+    pub coinbase: Option<String>,
+
+    /// Stratum 矿工名称
+    #[arg(long, default_value = "bitnice-miner")]
+    pub worker: String,
+
+    /// Stratum 登录密码
+    #[arg(long, default_value = "x")]
+    pub password: String,
+
+    /// 启用详细日志
+    #[arg(long)]
+    pub verbose: bool,
+}
+
+/// 矿工连接的端点
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Endpoint {
+    /// 节点 RPC，使用 `bitnice_getWork` / `bitnice_submitWork`
+    Node(String),
+    /// Stratum v1 矿池，值为 `host:port`
+    Stratum(String),
+}
+
+impl Cli {
+    /// 解析命令行参数，参数冲突时按 clap 的方式报错退出
+    pub fn parse_args() -> Self {
+        let cli = Self::parse();
+        if let Err(err) = cli.validate() {
+            err.exit();
+        }
+        cli
+    }
+
+    /// 检查参数组合：节点模式下不接受 `--coinbase`
+    pub fn validate(&self) -> Result<(), clap::Error> {
+        if self.coinbase.is_some() && matches!(self.endpoint(), Ok(Endpoint::Node(_))) {
+            return Err(Self::command().error(
+                ErrorKind::ArgumentConflict,
+                "--coinbase 只能与 stratum+tcp:// 地址一起使用，节点模式的奖励由节点 `mine --coinbase` 决定",
+            ));
+        }
+        Ok(())
+    }
+
+    /// 获取挖矿线程数
+    pub fn threads(&self) -> usize {
+        if self.threads == 0 {
+            num_cpus::get()
+        } else {
+            self.threads
+        }
+    }
+
+    /// 解析连接端点
+    pub fn endpoint(&self) -> Result<Endpoint, Error> {
+        parse_endpoint(&self.url)
+    }
+
+    /// 解析挖矿奖励账户
+    pub fn coinbase_account(&self) -> Result<Option<AccountId32>, Error> {
+        self.coinbase
+            .as_deref()
+            .map(|address| bitnice_pow::parse_coinbase(address).map_err(Error::Input))
+            .transpose()
+    }
+
+    /// Stratum 登录名：`<coinbase>.<worker>`，未指定奖励地址时只使用矿工名称
+    pub fn login(&self) -> String {
+        match &self.coinbase {
+            Some(coinbase) => format!("{}.{}", coinbase, self.worker),
+            None => self.worker.clone(),
+        }
+    }
+}
+
+/// 按 URL 协议区分节点 RPC 与 Stratum 端点
+pub fn parse_endpoint(url: &str) -> Result<Endpoint, Error> {
+    if let Some(address) = url.strip_prefix("stratum+tcp://") {
+        return Ok(Endpoint::Stratum(address.trim_end_matches('/').to_string()));
+    }
+
+    let supported = ["ws://", "wss://", "http://", "https://"];
+    if supported.iter().any(|scheme| url.starts_with(scheme)) {
+        Ok(Endpoint::Node(url.to_string()))
+    } else {
+        Err(Error::Input(format!(
+            "不支持的地址 {}，应以 ws://、http:// 或 stratum+tcp:// 开头",
+            url
+        )))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_endpoint() {
+        assert_eq!(
+            parse_endpoint("ws://127.0.0.1:9944").unwrap(),
+            Endpoint::Node("ws://127.0.0.1:9944".into())
+        );
+        assert_eq!(
+            parse_endpoint("stratum+tcp://pool.example.com:3333").unwrap(),
+            Endpoint::Stratum("pool.example.com:3333".into())
+        );
+        assert!(parse_endpoint("127.0.0.1:3333").is_err());
+    }
+
+    #[test]
+    fn test_cli_parsing() {
+        let cli = Cli::try_parse_from([
+            "bitnice-miner",
+            "--url",
+            "stratum+tcp://127.0.0.1:3333",
+            "--threads",
+            "4",
+            "--coinbase",
+            "5GrwvaEF5zXb26Fz9rcQpDWS57CtERHpNehXCPcNoHGKutQY",
+            "--worker",
+            "rig1",
+        ])
+        .unwrap();
+
+        assert_eq!(cli.threads(), 4);
+        assert!(cli.coinbase_account().unwrap().is_some());
+        assert_eq!(
+            cli.login(),
+            "5GrwvaEF5zXb26Fz9rcQpDWS57CtERHpNehXCPcNoHGKutQY.rig1"
+        );
+
+        cli.validate().unwrap();
+    }
+
+    #[test]
+    fn test_coinbase_rejected_in_node_mode() {
+        let cli = Cli::try_parse_from([
+            "bitnice-miner",
+            "--url",
+            "ws://127.0.0.1:9944",
+            "--coinbase",
+            "5GrwvaEF5zXb26Fz9rcQpDWS57CtERHpNehXCPcNoHGKutQY",
+        ])
+        .unwrap();
+        assert_eq!(
+            cli.validate().unwrap_err().kind(),
+            ErrorKind::ArgumentConflict
+        );
+
+        // 节点模式不指定奖励地址
+        let cli = Cli::try_parse_from(["bitnice-miner", "--url", "ws://127.0.0.1:9944"]).unwrap();
+        cli.validate().unwrap();
+    }
+}
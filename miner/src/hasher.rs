@@ -0,0 +1,279 @@
+//! 哈希线程
+//!
+//! 封印哈希与难度判断使用 `bitnice_pow`，与节点的导入校验一致。
+//! 各线程在当前工作的随机数空间中划分互不重叠的区间搜索，找到的解发送给连接任务提交。
+//! 节点分配的 extranonce 允许滚动时，线程区间用尽后滚动 extranonce 继续搜索。
+
+use std::{
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc, RwLock,
+    },
+    thread,
+    time::Duration,
+};
+
+use bitnice_pow::Seal;
+use sp_core::{H256, U256};
+use tokio::sync::mpsc::UnboundedSender;
+use tracing::{debug, warn};
+
+/// 每批尝试的随机数数量，批次之间检查工作是否更新
+const NONCES_PER_BATCH: u64 = 10_000;
+
+/// 没有工作时的等待时间
+const IDLE_WAIT: Duration = Duration::from_millis(500);
+
+/// 一份挖矿工作
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Job {
+    /// 工作编号，提交时原样带回
+    pub id: String,
+    /// 区块预哈希
+    pub pre_hash: H256,
+    /// 解需要满足的难度（节点模式为区块难度，Stratum 模式为份额难度）
+    pub difficulty: U256,
+    /// 随机数中由服务端固定的高位
+    pub nonce_prefix: u64,
+    /// 随机数中矿工可自由搜索的低位位数
+    pub free_bits: u32,
+    /// 服务端分配的 extranonce
+    pub extranonce: u64,
+    /// 是否允许滚动 extranonce 的低 32 位（节点模式）
+    pub roll_extranonce: bool,
+}
+
+impl Job {
+    /// 第 `index` 个线程负责的随机数偏移区间 `[start, end)`
+    pub fn thread_range(&self, index: usize, threads: usize) -> (u128, u128) {
+        let threads = threads.max(1) as u128;
+        let index = index as u128;
+        let space = 1u128 << self.free_bits.min(64);
+        let span = space / threads;
+        let start = index * span;
+        let end = if index + 1 == threads {
+            space
+        } else {
+            start + span
+        };
+
+        (start, end)
+    }
+
+    /// 由偏移得到完整随机数
+    pub fn nonce(&self, offset: u128) -> u64 {
+        self.nonce_prefix | offset as u64
+    }
+
+    /// 第 `roll` 次滚动后的 extranonce，只改变低 32 位
+    pub fn extranonce(&self, roll: u32) -> u64 {
+        (self.extranonce & !0xffff_ffff) | u64::from((self.extranonce as u32).wrapping_add(roll))
+    }
+}
+
+/// 找到的解
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Solution {
+    /// 对应的工作编号
+    pub job_id: String,
+    /// 随机数
+    pub nonce: u64,
+    /// 封印使用的 extranonce
+    pub extranonce: u64,
+}
+
+/// 当前工作，连接任务写入，哈希线程读取
+#[derive(Clone, Default)]
+pub struct JobBoard {
+    current: Arc<RwLock<Option<Arc<Job>>>>,
+    version: Arc<AtomicU64>,
+}
+
+impl JobBoard {
+    /// 替换当前工作，工作未变化时不打断哈希线程
+    pub fn set(&self, job: Option<Job>) {
+        let mut current = self.current.write().expect("工作锁未被污染");
+        if current.as_deref() == job.as_ref() {
+            return;
+        }
+
+        if let Some(job) = &job {
+            debug!("新工作 {}，难度 {}", job.id, job.difficulty);
+        }
+        *current = job.map(Arc::new);
+        self.version.fetch_add(1, Ordering::SeqCst);
+    }
+
+    /// 当前工作及其版本
+    pub fn get(&self) -> (u64, Option<Arc<Job>>) {
+        let current = self.current.read().expect("工作锁未被污染");
+        (self.version.load(Ordering::SeqCst), current.clone())
+    }
+
+    /// 当前版本
+    pub fn version(&self) -> u64 {
+        self.version.load(Ordering::SeqCst)
+    }
+}
+
+/// 挖矿统计
+#[derive(Default)]
+pub struct Stats {
+    /// 每个线程累计的哈希次数
+    pub hashes: Vec<AtomicU64>,
+    /// 被接受的解
+    pub accepted: AtomicU64,
+    /// 因工作过期未被接受的解
+    pub stale: AtomicU64,
+    /// 被拒绝的解
+    pub rejected: AtomicU64,
+}
+
+impl Stats {
+    /// 为 `threads` 个线程创建统计
+    pub fn new(threads: usize) -> Self {
+        Self {
+            hashes: (0..threads).map(|_| AtomicU64::new(0)).collect(),
+            ..Default::default()
+        }
+    }
+}
+
+/// 启动哈希线程
+pub fn start(
+    threads: usize,
+    board: JobBoard,
+    solutions: UnboundedSender<Solution>,
+    stats: Arc<Stats>,
+) {
+    for index in 0..threads {
+        let board = board.clone();
+        let solutions = solutions.clone();
+        let stats = stats.clone();
+        let result = thread::Builder::new()
+            .name(format!("bitnice-hasher-{}", index))
+            .spawn(move || hash(index, threads, board, solutions, stats));
+
+        if let Err(err) = result {
+            warn!("⚠️  无法启动哈希线程 {}: {}", index, err);
+        }
+    }
+}
+
+/// 单个哈希线程的主循环
+fn hash(
+    index: usize,
+    threads: usize,
+    board: JobBoard,
+    solutions: UnboundedSender<Solution>,
+    stats: Arc<Stats>,
+) {
+    loop {
+        let (version, job) = board.get();
+        let job = match job {
+            Some(job) => job,
+            None => {
+                thread::sleep(IDLE_WAIT);
+                continue;
+            }
+        };
+
+        let (start, end) = job.thread_range(index, threads);
+        let mut offset = start;
+        let mut roll = 0;
+
+        // 同一工作下持续搜索，直到工作更新；Stratum 份额可能有多个解
+        while board.version() == version {
+            for _ in 0..NONCES_PER_BATCH {
+                let nonce = job.nonce(offset);
+                let extranonce = job.extranonce(roll);
+                // 区间用尽后回到起点，允许时滚动 extranonce
+                offset = if offset + 1 >= end {
+                    if job.roll_extranonce {
+                        roll = roll.wrapping_add(1);
+                    }
+                    start
+                } else {
+                    offset + 1
+                };
+
+                let seal = Seal::new(nonce, extranonce);
+                let hash = bitnice_pow::compute_hash(&job.pre_hash, &seal);
+                if bitnice_pow::hash_meets_difficulty(&hash, job.difficulty) {
+                    let solution = Solution {
+                        job_id: job.id.clone(),
+                        nonce,
+                        extranonce,
+                    };
+                    // 连接任务退出时程序也随之结束
+                    if solutions.send(solution).is_err() {
+                        return;
+                    }
+                }
+            }
+            stats.hashes[index].fetch_add(NONCES_PER_BATCH, Ordering::Relaxed);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn job(nonce_prefix: u64, free_bits: u32) -> Job {
+        Job {
+            id: "1".into(),
+            pre_hash: H256::repeat_byte(0x11),
+            difficulty: U256::one(),
+            nonce_prefix,
+            free_bits,
+            extranonce: 0,
+            roll_extranonce: false,
+        }
+    }
+
+    #[test]
+    fn test_thread_ranges_cover_free_space() {
+        // 节点模式：整个 64 位空间
+        let full = job(0, 64);
+        assert_eq!(full.thread_range(0, 1), (0, 1 << 64));
+        let ranges: Vec<_> = (0..3).map(|i| full.thread_range(i, 3)).collect();
+        assert_eq!(ranges[0].0, 0);
+        assert_eq!(ranges[2].1, 1 << 64);
+        assert_eq!(ranges[0].1, ranges[1].0);
+        assert_eq!(ranges[1].1, ranges[2].0);
+
+        // Stratum 模式：高 16 位为 extranonce1
+        let stratum = job(0xabcd << 48, 48);
+        let (start, end) = stratum.thread_range(1, 2);
+        assert_eq!(stratum.nonce(start), 0xabcd_8000_0000_0000);
+        assert_eq!(stratum.nonce(end - 1), 0xabcd_ffff_ffff_ffff);
+    }
+
+    #[test]
+    fn test_extranonce_rolls_low_bits() {
+        let job = Job {
+            extranonce: (1 << 62) | (7 << 32) | 0xffff_fffe,
+            ..job(0, 64)
+        };
+        assert_eq!(job.extranonce(0), job.extranonce);
+        assert_eq!(job.extranonce(1), (1 << 62) | (7 << 32) | 0xffff_ffff);
+        // 低 32 位回绕时不改变节点分配的高位
+        assert_eq!(job.extranonce(2), (1 << 62) | (7 << 32));
+    }
+
+    #[test]
+    fn test_job_board_skips_unchanged_job() {
+        let board = JobBoard::default();
+        board.set(Some(job(0, 64)));
+        let version = board.version();
+
+        // 相同工作不更新版本
+        board.set(Some(job(0, 64)));
+        assert_eq!(board.version(), version);
+
+        board.set(None);
+        assert_eq!(board.version(), version + 1);
+        assert!(board.get().1.is_none());
+    }
+}
@@ -0,0 +1,142 @@
+//! BitNice 独立矿工
+//!
+//! 连接节点 RPC 或 Stratum 矿池获取工作，多线程搜索 SHA-256 封印并提交，
+//! 定期输出算力与接受、过期、拒绝的解数量，连接断开后自动重连。
+
+mod cli;
+mod hasher;
+mod node;
+mod stratum;
+
+use std::{
+    sync::{atomic::Ordering, Arc},
+    time::{Duration, Instant},
+};
+
+use tokio::sync::mpsc;
+use tracing::{info, warn};
+
+use cli::{Cli, Endpoint};
+use hasher::{JobBoard, Solution, Stats};
+
+/// 算力统计的输出间隔
+const REPORT_INTERVAL: Duration = Duration::from_secs(10);
+
+/// 连接断开后重连前的等待时间
+const RECONNECT_DELAY: Duration = Duration::from_secs(5);
+
+/// 哈希线程发送给连接任务的解
+pub type Solutions = mpsc::UnboundedReceiver<Solution>;
+
+/// 矿工错误类型
+#[derive(Debug, thiserror::Error)]
+pub enum Error {
+    #[error("参数错误: {0}")]
+    Input(String),
+
+    #[error("RPC 错误: {0}")]
+    Rpc(#[from] jsonrpsee::core::client::Error),
+
+    #[error("网络错误: {0}")]
+    Io(#[from] std::io::Error),
+
+    #[error("协议错误: {0}")]
+    Protocol(String),
+}
+
+#[tokio::main]
+async fn main() -> Result<(), Error> {
+    tracing_subscriber::fmt()
+        .with_env_filter(
+            tracing_subscriber::EnvFilter::try_from_default_env().unwrap_or_else(|_| "info".into()),
+        )
+        .init();
+
+    let cli = Cli::parse_args();
+    let endpoint = cli.endpoint()?;
+    // 地址格式或前缀错误时直接退出
+    cli.coinbase_account()?;
+    let threads = cli.threads();
+
+    info!("🚀 启动 BitNice 矿工");
+    info!("⚡ 挖矿线程数: {}", threads);
+    if let Endpoint::Stratum(_) = endpoint {
+        info!("👷 Stratum 登录名: {}", cli.login());
+    }
+
+    let board = JobBoard::default();
+    let stats = Arc::new(Stats::new(threads));
+    let (solutions_tx, mut solutions) = mpsc::unbounded_channel();
+    hasher::start(threads, board.clone(), solutions_tx, stats.clone());
+    tokio::spawn(report(stats.clone(), cli.verbose));
+
+    loop {
+        let result = match &endpoint {
+            Endpoint::Node(url) => node::run(url, &board, &mut solutions, &stats).await,
+            Endpoint::Stratum(address) => {
+                stratum::run(
+                    address,
+                    &cli.login(),
+                    &cli.password,
+                    &board,
+                    &mut solutions,
+                    &stats,
+                )
+                .await
+            }
+        };
+
+        // 断线期间停止搜索，丢弃旧工作的解
+        board.set(None);
+        while solutions.try_recv().is_ok() {}
+
+        if let Err(err) = result {
+            warn!(
+                "⚠️  连接断开: {}，{} 秒后重连",
+                err,
+                RECONNECT_DELAY.as_secs()
+            );
+        }
+        tokio::time::sleep(RECONNECT_DELAY).await;
+    }
+}
+
+/// 定期输出算力和解的统计
+async fn report(stats: Arc<Stats>, verbose: bool) {
+    let mut last: Vec<u64> = vec![0; stats.hashes.len()];
+    let mut last_report = Instant::now();
+    let mut interval = tokio::time::interval(REPORT_INTERVAL);
+    // 第一次 tick 立即完成，跳过
+    interval.tick().await;
+
+    loop {
+        interval.tick().await;
+
+        let elapsed = last_report.elapsed().as_secs_f64();
+        last_report = Instant::now();
+
+        let mut total = 0;
+        for (index, (counter, last)) in stats.hashes.iter().zip(last.iter_mut()).enumerate() {
+            let hashes = counter.load(Ordering::Relaxed);
+            let delta = hashes - *last;
+            *last = hashes;
+            total += delta;
+
+            if verbose {
+                info!(
+                    "⛏️  线程 {} 算力: {:.2} kH/s",
+                    index,
+                    delta as f64 / elapsed / 1000.0
+                );
+            }
+        }
+
+        info!(
+            "⛏️  算力: {:.2} kH/s，接受 {}，过期 {}，拒绝 {}",
+            total as f64 / elapsed / 1000.0,
+            stats.accepted.load(Ordering::Relaxed),
+            stats.stale.load(Ordering::Relaxed),
+            stats.rejected.load(Ordering::Relaxed),
+        );
+    }
+}
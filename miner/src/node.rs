@@ -0,0 +1,175 @@
+//! 节点 RPC 连接
+//!
+//! 通过 `bitnice_getWork` 获取工作、`bitnice_submitWork` 提交封印。
+//! WebSocket 连接订阅 `bitnice_subscribeWork` 接收新工作，HTTP 连接定期轮询。
+//! 节点为每次请求分配不同的 extranonce，轮询到相同编号的工作时保留当前工作，避免打断哈希线程。
+
+use std::time::Duration;
+
+use bitnice_pow::Seal;
+use codec::Encode;
+use jsonrpsee::{
+    core::client::{ClientT, Error as ClientError, SubscriptionClientT},
+    http_client::HttpClientBuilder,
+    rpc_params,
+    ws_client::WsClientBuilder,
+};
+use sp_core::{Bytes, H256, U256};
+use tracing::{info, warn};
+
+use crate::{
+    hasher::{Job, JobBoard, Solution, Stats},
+    Error, Solutions,
+};
+
+/// HTTP 模式下轮询工作的间隔
+const POLL_INTERVAL: Duration = Duration::from_millis(500);
+
+/// 节点返回的无工作、工作过期与封印无效错误码
+const ERR_NO_WORK: i32 = 9005;
+const ERR_STALE_WORK: i32 = 9006;
+const ERR_INVALID_SEAL: i32 = 9007;
+
+/// 节点下发的工作（`bitnice_getWork` 的返回值）
+#[derive(Debug, Clone, serde::Deserialize)]
+pub struct Work {
+    /// 工作编号
+    pub work_id: u64,
+    /// 区块预哈希
+    pub pre_hash: H256,
+    /// 区块难度
+    pub difficulty: U256,
+    /// 节点分配的 extranonce，旧版本节点不返回时为 0
+    #[serde(default)]
+    pub extranonce: u64,
+}
+
+impl From<Work> for Job {
+    fn from(work: Work) -> Self {
+        Job {
+            id: work.work_id.to_string(),
+            pre_hash: work.pre_hash,
+            difficulty: work.difficulty,
+            nonce_prefix: 0,
+            free_bits: 64,
+            extranonce: work.extranonce,
+            roll_extranonce: true,
+        }
+    }
+}
+
+/// 连接节点并挖矿，直到连接出错
+pub async fn run(
+    url: &str,
+    board: &JobBoard,
+    solutions: &mut Solutions,
+    stats: &Stats,
+) -> Result<(), Error> {
+    if url.starts_with("ws") {
+        run_ws(url, board, solutions, stats).await
+    } else {
+        run_http(url, board, solutions, stats).await
+    }
+}
+
+async fn run_ws(
+    url: &str,
+    board: &JobBoard,
+    solutions: &mut Solutions,
+    stats: &Stats,
+) -> Result<(), Error> {
+    let client = WsClientBuilder::default().build(url).await?;
+    info!("🔗 已连接节点 {}", url);
+
+    let mut subscription = client
+        .subscribe::<Work, _>(
+            "bitnice_subscribeWork",
+            rpc_params![],
+            "bitnice_unsubscribeWork",
+        )
+        .await?;
+    // 订阅只在模板变化时推送，先主动获取一次
+    fetch_work(&client, board).await?;
+
+    loop {
+        tokio::select! {
+            work = subscription.next() => match work {
+                Some(Ok(work)) => board.set(Some(work.into())),
+                Some(Err(err)) => return Err(Error::Protocol(format!("无法解析工作: {}", err))),
+                None => return Err(Error::Protocol("工作订阅已关闭".into())),
+            },
+            Some(solution) = solutions.recv() => submit(&client, solution, stats).await?,
+        }
+    }
+}
+
+async fn run_http(
+    url: &str,
+    board: &JobBoard,
+    solutions: &mut Solutions,
+    stats: &Stats,
+) -> Result<(), Error> {
+    let client = HttpClientBuilder::default().build(url)?;
+    info!("🔗 轮询节点 {}", url);
+
+    let mut interval = tokio::time::interval(POLL_INTERVAL);
+    loop {
+        tokio::select! {
+            _ = interval.tick() => fetch_work(&client, board).await?,
+            Some(solution) = solutions.recv() => submit(&client, solution, stats).await?,
+        }
+    }
+}
+
+/// 获取当前工作，节点暂无模板时清空工作
+async fn fetch_work<C: ClientT>(client: &C, board: &JobBoard) -> Result<(), Error> {
+    match client
+        .request::<Work, _>("bitnice_getWork", rpc_params![])
+        .await
+    {
+        Ok(work) => {
+            let work_id = work.work_id.to_string();
+            if !board.get().1.is_some_and(|job| job.id == work_id) {
+                board.set(Some(work.into()));
+            }
+        }
+        Err(ClientError::Call(err)) if err.code() == ERR_NO_WORK => board.set(None),
+        Err(err) => return Err(err.into()),
+    }
+
+    Ok(())
+}
+
+/// 提交封印，只有连接错误会中断会话
+async fn submit<C: ClientT>(client: &C, solution: Solution, stats: &Stats) -> Result<(), Error> {
+    let work_id: u64 = solution
+        .job_id
+        .parse()
+        .map_err(|_| Error::Protocol(format!("无效的工作编号 {}", solution.job_id)))?;
+    let seal = Bytes(Seal::new(solution.nonce, solution.extranonce).encode());
+
+    let counter = match client
+        .request::<bool, _>("bitnice_submitWork", rpc_params![work_id, seal])
+        .await
+    {
+        Ok(true) => {
+            info!("✨ 节点接受了封印，nonce = {}", solution.nonce);
+            &stats.accepted
+        }
+        // 模板已被其他封印取走
+        Ok(false) => &stats.stale,
+        Err(ClientError::Call(err)) if matches!(err.code(), ERR_NO_WORK | ERR_STALE_WORK) => {
+            &stats.stale
+        }
+        Err(ClientError::Call(err)) => {
+            if err.code() != ERR_INVALID_SEAL {
+                warn!("⚠️  提交封印失败: {}", err.message());
+            }
+            &stats.rejected
+        }
+        Err(err) => return Err(err.into()),
+    };
+    counter.fetch_add(1, std::sync::atomic::Ordering::Relaxed);
+
+    Ok(())
+}
@@ -0,0 +1,255 @@
+//! Stratum v1 矿池连接
+//!
+//! 与节点内置的 Stratum 服务配合：订阅后获得 2 字节 extranonce1，
+//! 矿工在剩余 48 位中搜索，提交时拆分为 2 字节 extranonce2 与 4 字节 nonce。
+
+use std::{collections::HashSet, sync::atomic::Ordering};
+
+use serde_json::{json, Value};
+use sp_core::{H256, U256};
+use tokio::{
+    io::{AsyncBufReadExt, AsyncWrite, AsyncWriteExt, BufReader},
+    net::TcpStream,
+};
+use tracing::{info, warn};
+
+use crate::{
+    hasher::{Job, JobBoard, Stats},
+    Error, Solutions,
+};
+
+/// 订阅与登录请求的编号
+const SUBSCRIBE_ID: u64 = 1;
+const AUTHORIZE_ID: u64 = 2;
+
+/// 服务端返回的工作不存在错误码
+const ERR_JOB_NOT_FOUND: i64 = 21;
+
+/// 矿工自由搜索的位数：2 字节 extranonce2 + 4 字节 nonce
+const FREE_BITS: u32 = 48;
+
+/// 会话状态
+#[derive(Default)]
+struct Session {
+    extranonce1: Option<u16>,
+    difficulty: U256,
+    next_id: u64,
+    pending: HashSet<u64>,
+}
+
+/// 将随机数拆分为 Stratum 提交参数中的 extranonce2 和 nonce
+pub fn split_nonce(nonce: u64) -> (String, String) {
+    let extranonce2 = (nonce >> 32) as u16;
+    let nonce = nonce as u32;
+
+    (format!("{:04x}", extranonce2), format!("{:08x}", nonce))
+}
+
+/// 连接矿池并挖矿，直到连接出错
+pub async fn run(
+    address: &str,
+    login: &str,
+    password: &str,
+    board: &JobBoard,
+    solutions: &mut Solutions,
+    stats: &Stats,
+) -> Result<(), Error> {
+    let stream = TcpStream::connect(address).await?;
+    let (reader, mut writer) = stream.into_split();
+    let mut lines = BufReader::new(reader).lines();
+    info!("🔗 已连接矿池 {}", address);
+
+    let agent = concat!("bitnice-miner/", env!("CARGO_PKG_VERSION"));
+    send(
+        &mut writer,
+        &json!({ "id": SUBSCRIBE_ID, "method": "mining.subscribe", "params": [agent] }),
+    )
+    .await?;
+    send(
+        &mut writer,
+        &json!({ "id": AUTHORIZE_ID, "method": "mining.authorize", "params": [login, password] }),
+    )
+    .await?;
+
+    let mut session = Session {
+        difficulty: U256::one(),
+        next_id: AUTHORIZE_ID + 1,
+        ..Default::default()
+    };
+
+    loop {
+        tokio::select! {
+            line = lines.next_line() => {
+                let line = line?.ok_or_else(|| Error::Protocol("矿池关闭了连接".into()))?;
+                if line.trim().is_empty() {
+                    continue;
+                }
+                let message: Value = serde_json::from_str(&line)
+                    .map_err(|err| Error::Protocol(format!("无法解析矿池消息: {}", err)))?;
+                session.handle(message, board, stats)?;
+            }
+            Some(solution) = solutions.recv() => {
+                let id = session.next_id;
+                session.next_id += 1;
+                session.pending.insert(id);
+
+                let (extranonce2, nonce) = split_nonce(solution.nonce);
+                let params = json!([login, solution.job_id, extranonce2, nonce]);
+                send(&mut writer, &json!({ "id": id, "method": "mining.submit", "params": params })).await?;
+            }
+        }
+    }
+}
+
+impl Session {
+    /// 处理矿池推送或响应
+    fn handle(&mut self, message: Value, board: &JobBoard, stats: &Stats) -> Result<(), Error> {
+        if let Some(method) = message["method"].as_str() {
+            let params = &message["params"];
+            match method {
+                "mining.set_difficulty" => {
+                    let difficulty = &params[0];
+                    // 新难度从下一份工作开始生效
+                    self.difficulty = difficulty
+                        .as_u64()
+                        .map(U256::from)
+                        .or_else(|| {
+                            difficulty
+                                .as_f64()
+                                .map(|difficulty| U256::from(difficulty as u128))
+                        })
+                        .unwrap_or_default()
+                        .max(U256::one());
+                }
+                "mining.notify" => self.notify(params, board)?,
+                _ => {}
+            }
+            return Ok(());
+        }
+
+        let id = message["id"].as_u64();
+        let error = &message["error"];
+        match id {
+            Some(SUBSCRIBE_ID) => {
+                let extranonce1 = message["result"][1]
+                    .as_str()
+                    .and_then(|hex| u16::from_str_radix(hex, 16).ok())
+                    .ok_or_else(|| Error::Protocol(format!("订阅失败: {}", error)))?;
+                self.extranonce1 = Some(extranonce1);
+            }
+            Some(AUTHORIZE_ID) => {
+                if message["result"] != Value::Bool(true) {
+                    return Err(Error::Protocol(format!("矿池拒绝登录: {}", error)));
+                }
+                info!("👷 已登录矿池");
+            }
+            Some(id) if self.pending.remove(&id) => {
+                let counter = if message["result"] == Value::Bool(true) {
+                    &stats.accepted
+                } else if error[0].as_i64() == Some(ERR_JOB_NOT_FOUND) {
+                    &stats.stale
+                } else {
+                    warn!("⚠️  份额被拒绝: {}", error);
+                    &stats.rejected
+                };
+                counter.fetch_add(1, Ordering::Relaxed);
+            }
+            _ => {}
+        }
+
+        Ok(())
+    }
+
+    /// 处理 `mining.notify`：`[job_id, pre_hash, parent_hash, clean_jobs]`
+    fn notify(&mut self, params: &Value, board: &JobBoard) -> Result<(), Error> {
+        // 订阅完成前的工作无法确定随机数前缀
+        let extranonce1 = match self.extranonce1 {
+            Some(extranonce1) => extranonce1,
+            None => return Ok(()),
+        };
+
+        let id = params[0]
+            .as_str()
+            .ok_or_else(|| Error::Protocol("工作缺少编号".into()))?;
+        let pre_hash = params[1]
+            .as_str()
+            .and_then(|hex| hex::decode(hex.trim_start_matches("0x")).ok())
+            .filter(|bytes| bytes.len() == 32)
+            .ok_or_else(|| Error::Protocol(format!("工作 {} 的预哈希无效", id)))?;
+
+        board.set(Some(Job {
+            id: id.to_string(),
+            pre_hash: H256::from_slice(&pre_hash),
+            difficulty: self.difficulty,
+            nonce_prefix: u64::from(extranonce1) << FREE_BITS,
+            free_bits: FREE_BITS,
+            extranonce: 0,
+            roll_extranonce: false,
+        }));
+
+        Ok(())
+    }
+}
+
+/// 发送一行 JSON 消息
+async fn send<W: AsyncWrite + Unpin>(writer: &mut W, message: &Value) -> Result<(), Error> {
+    let mut line = message.to_string();
+    line.push('\n');
+    writer.write_all(line.as_bytes()).await?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_split_nonce() {
+        let nonce = 0xabcd_1234_dead_beef;
+        assert_eq!(
+            split_nonce(nonce),
+            ("1234".to_string(), "deadbeef".to_string())
+        );
+    }
+
+    #[test]
+    fn test_notify_uses_extranonce1_and_difficulty() {
+        let board = JobBoard::default();
+        let stats = Stats::new(1);
+        let mut session = Session {
+            difficulty: U256::one(),
+            ..Default::default()
+        };
+
+        let pre_hash = "11".repeat(32);
+        let notify =
+            json!({ "id": null, "method": "mining.notify", "params": ["7", pre_hash, "", true] });
+
+        // 订阅完成前忽略工作
+        session.handle(notify.clone(), &board, &stats).unwrap();
+        assert!(board.get().1.is_none());
+
+        session
+            .handle(
+                json!({ "id": SUBSCRIBE_ID, "result": [[], "abcd", 2], "error": null }),
+                &board,
+                &stats,
+            )
+            .unwrap();
+        session
+            .handle(
+                json!({ "id": null, "method": "mining.set_difficulty", "params": [1000] }),
+                &board,
+                &stats,
+            )
+            .unwrap();
+        session.handle(notify, &board, &stats).unwrap();
+
+        let job = board.get().1.unwrap();
+        assert_eq!(job.id, "7");
+        assert_eq!(job.difficulty, U256::from(1000u64));
+        assert_eq!(job.nonce_prefix, 0xabcd << 48);
+        assert_eq!(job.pre_hash, H256::repeat_byte(0x11));
+    }
+}
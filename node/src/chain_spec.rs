@@ -1,21 +1,83 @@
 //! BitNice 链规范配置
 //!
-//! 极简版本 - 只提供基本的开发链配置
+//! 提供开发链、本地测试网和预发布测试网三种链规范，创世状态来自运行时预设。
+//! 开发链和本地测试网使用私钥公开的开发账户，预发布测试网使用运营方账户
 
-use sc_service::{ChainType, GenericChainSpec};
+use sc_service::{config::MultiaddrWithPeerId, ChainType, GenericChainSpec, Properties};
+
+use bitnice_runtime::{genesis_config_presets::STAGING_RUNTIME_PRESET, SS58_PREFIX, WASM_BINARY};
 
 /// BitNice 链规范类型 - 使用Option<()>因为()没有实现ChainSpecExtension
 pub type ChainSpec = GenericChainSpec<Option<()>>;
 
+/// 网络协议 ID
+const PROTOCOL_ID: &str = "bitnice";
+
+/// 预发布测试网的启动节点（占位地址）
+///
+/// 部署后替换为实际主机名与 `bitnice-node key inspect-node-key` 输出的 peer id
+const STAGING_BOOT_NODES: &[&str] = &[
+    "/dns/boot-0.staging.bitnice.example/tcp/30333/p2p/12D3KooWFbXvZedkewCRKx3jiQheTH4JVtfdoec7igLYVWQtYeZ8",
+    "/dns/boot-1.staging.bitnice.example/tcp/30333/p2p/12D3KooWA9XPZ44vu4gMcEeChorW7aRSQ27KoGuiVks6tuaYUgx1",
+];
+
+/// 链属性：代币符号、精度与地址前缀
+fn chain_properties() -> Properties {
+    let mut properties = Properties::new();
+    properties.insert("tokenSymbol".into(), "BN".into());
+    properties.insert("tokenDecimals".into(), 12.into());
+    properties.insert("ss58Format".into(), SS58_PREFIX.into());
+    properties
+}
+
+/// 解析启动节点地址
+fn boot_nodes(addresses: &[&str]) -> Result<Vec<MultiaddrWithPeerId>, String> {
+    addresses
+        .iter()
+        .map(|address| {
+            address
+                .parse()
+                .map_err(|err| format!("无效的启动节点地址 {}: {}", address, err))
+        })
+        .collect()
+}
+
 /// 开发配置
 pub fn development_config() -> Result<ChainSpec, String> {
     Ok(ChainSpec::builder(
-        bitnice_runtime::WASM_BINARY.ok_or("WASM二进制文件未找到")?,
+        WASM_BINARY.ok_or("WASM二进制文件未找到")?,
         None::<()>  // 使用None而不是空的扩展
     )
     .with_name("BitNice Development")
     .with_id("bitnice_dev")
     .with_chain_type(ChainType::Development)
-    .with_genesis_config_patch(serde_json::json!({}))
+    .with_genesis_config_preset_name(sp_genesis_builder::DEV_RUNTIME_PRESET)
+    .with_protocol_id(PROTOCOL_ID)
+    .with_properties(chain_properties())
     .build())
 }
+
+/// 本地测试网配置
+pub fn local_testnet_config() -> Result<ChainSpec, String> {
+    Ok(ChainSpec::builder(WASM_BINARY.ok_or("WASM二进制文件未找到")?, None::<()>)
+        .with_name("BitNice Local Testnet")
+        .with_id("bitnice_local")
+        .with_chain_type(ChainType::Local)
+        .with_genesis_config_preset_name(sp_genesis_builder::LOCAL_TESTNET_RUNTIME_PRESET)
+        .with_protocol_id(PROTOCOL_ID)
+        .with_properties(chain_properties())
+        .build())
+}
+
+/// 预发布测试网配置
+pub fn staging_testnet_config() -> Result<ChainSpec, String> {
+    Ok(ChainSpec::builder(WASM_BINARY.ok_or("WASM二进制文件未找到")?, None::<()>)
+        .with_name("BitNice Staging Testnet")
+        .with_id("bitnice_staging")
+        .with_chain_type(ChainType::Live)
+        .with_genesis_config_preset_name(STAGING_RUNTIME_PRESET)
+        .with_boot_nodes(boot_nodes(STAGING_BOOT_NODES)?)
+        .with_protocol_id(PROTOCOL_ID)
+        .with_properties(chain_properties())
+        .build())
+}
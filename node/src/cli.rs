@@ -2,9 +2,17 @@
 //!
 //! 定义了所有可用的命令行参数和子命令
 
+use std::net::SocketAddr;
+
 use clap::Parser;
 use sc_cli::RunCmd;
 
+use bitnice_pow::parse_coinbase;
+use bitnice_runtime::{AccountId, Coinbase, MAX_PAYEES};
+use sp_runtime::{PerThing, Permill};
+
+use crate::stratum::StratumParams;
+
 /// BitNice 节点 CLI 参数解析器
 #[derive(Debug, clap::Parser)]
 pub struct Cli {
@@ -48,9 +56,10 @@ pub enum Subcommand {
     #[cfg(feature = "runtime-benchmarks")]
     Benchmark(frame_benchmarking_cli::BenchmarkCmd),
 
-    /// 尝试运行时命令
+    /// 尝试运行时命令已迁移到独立的 try-runtime 工具
+    /// (<https://github.com/paritytech/try-runtime-cli>)，这里只保留提示
     #[cfg(feature = "try-runtime")]
-    TryRuntime(try_runtime_cli::TryRuntimeCmd),
+    TryRuntime,
 
     /// 工作量证明挖矿命令
     Mine(MineCmd),
@@ -67,6 +76,11 @@ pub struct MineCmd {
     #[arg(long)]
     pub coinbase: Option<String>,
 
+    /// 区块奖励的收款账户与百万分比，例如 `5FHneW46...:250000` 表示 25%，可重复指定，
+    /// 剩余部分归奖励地址
+    #[arg(long = "payee", value_name = "ADDRESS:PPM", requires = "coinbase")]
+    pub payees: Vec<String>,
+
     /// 挖矿算法难度目标
     #[arg(long)]
     pub target: Option<u64>,
@@ -75,15 +89,37 @@ pub struct MineCmd {
     #[arg(long)]
     pub verbose: bool,
 
+    /// 不启动本地挖矿线程，只通过 RPC 或 Stratum 为外部矿工提供工作
+    #[arg(long)]
+    pub external: bool,
+
+    /// 在指定地址上开启 Stratum v1 矿池接口，例如 `0.0.0.0:3333`
+    #[arg(long, value_name = "ADDR")]
+    pub stratum: Option<SocketAddr>,
+
+    /// Stratum 份额难度
+    #[arg(long, default_value = "1000")]
+    pub stratum_difficulty: u128,
+
+    /// Stratum 以比特币 80 字节区块头模式下发工作，供 SHA-256d 矿机使用
+    #[arg(long, requires = "stratum")]
+    pub stratum_header: bool,
+
+    /// 测量本机各哈希实现的算力后退出，不启动节点
+    #[arg(long)]
+    pub bench: bool,
+
     /// 基础运行参数
     #[command(flatten)]
     pub base: RunCmd,
 }
 
 impl MineCmd {
-    /// 获取挖矿线程数
+    /// 获取挖矿线程数，外部挖矿模式下为 0
     pub fn threads(&self) -> usize {
-        if self.threads == 0 {
+        if self.external {
+            0
+        } else if self.threads == 0 {
             num_cpus::get()
         } else {
             self.threads
@@ -95,6 +131,39 @@ impl MineCmd {
         self.coinbase.as_deref()
     }
 
+    /// 解析挖矿奖励账户
+    pub fn coinbase_account(&self) -> Result<Option<AccountId>, sc_cli::Error> {
+        self.coinbase_address()
+            .map(|address| parse_coinbase(address).map_err(sc_cli::Error::Input))
+            .transpose()
+    }
+
+    /// 解析奖励账户与奖励分配，分配超过上限或合计超过 100% 时返回错误
+    pub fn coinbase(&self) -> Result<Option<Coinbase>, sc_cli::Error> {
+        let Some(author) = self.coinbase_account()? else {
+            return Ok(None);
+        };
+
+        let payees = self
+            .payees
+            .iter()
+            .map(|payee| parse_payee(payee))
+            .collect::<Result<Vec<_>, _>>()?;
+        let coinbase = Coinbase {
+            author,
+            payees: payees
+                .try_into()
+                .map_err(|_| sc_cli::Error::Input(format!("收款账户最多 {} 个", MAX_PAYEES)))?,
+        };
+        if !coinbase.is_valid() {
+            return Err(sc_cli::Error::Input(
+                "收款账户的分配比例必须大于 0 且合计不超过 100%".into(),
+            ));
+        }
+
+        Ok(Some(coinbase))
+    }
+
     /// 获取难度目标
     pub fn difficulty_target(&self) -> Option<u64> {
         self.target
@@ -104,6 +173,29 @@ impl MineCmd {
     pub fn is_verbose(&self) -> bool {
         self.verbose
     }
+
+    /// Stratum 服务参数，未指定监听地址时不开启
+    pub fn stratum_params(&self) -> Option<StratumParams> {
+        self.stratum.map(|listen| StratumParams {
+            listen,
+            share_difficulty: self.stratum_difficulty.into(),
+            header_mode: self.stratum_header,
+        })
+    }
+}
+
+/// 解析 `ADDRESS:PPM` 格式的收款账户，`PPM` 为百万分比
+pub fn parse_payee(payee: &str) -> Result<(AccountId, Permill), sc_cli::Error> {
+    let invalid =
+        || sc_cli::Error::Input(format!("无效的收款账户 {}，格式应为 ADDRESS:PPM", payee));
+    let (address, share) = payee.rsplit_once(':').ok_or_else(invalid)?;
+    let share: u32 = share.parse().map_err(|_| invalid())?;
+    if share > Permill::ACCURACY {
+        return Err(invalid());
+    }
+
+    let account = parse_coinbase(address).map_err(sc_cli::Error::Input)?;
+    Ok((account, Permill::from_parts(share)))
 }
 
 #[cfg(test)]
@@ -142,13 +234,62 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_coinbase_payees() {
+        let alice = "5GrwvaEF5zXb26Fz9rcQpDWS57CtERHpNehXCPcNoHGKutQY";
+        let bob = "5FHneW46xGXgs5mUiveU4sbTyGBzmstUspZC92UhjJM694ty";
+        let parse = |payees: &[String]| {
+            let mut args = vec![
+                "bitnice-node".to_string(),
+                "mine".into(),
+                "--coinbase".into(),
+                alice.into(),
+            ];
+            for payee in payees {
+                args.push("--payee".into());
+                args.push(payee.clone());
+            }
+            match Cli::try_parse_from(args).unwrap().subcommand {
+                Some(Subcommand::Mine(mine_cmd)) => mine_cmd.coinbase(),
+                _ => panic!("Expected Mine subcommand"),
+            }
+        };
+
+        let coinbase = parse(&[format!("{}:250000", bob)]).unwrap().unwrap();
+        assert_eq!(
+            coinbase.author,
+            sp_keyring::Sr25519Keyring::Alice.to_account_id()
+        );
+        assert_eq!(
+            coinbase.payees.into_inner(),
+            vec![(
+                sp_keyring::Sr25519Keyring::Bob.to_account_id(),
+                Permill::from_percent(25)
+            )]
+        );
+
+        // 合计超过 100% 或格式错误
+        assert!(parse(&[format!("{}:600000", bob), format!("{}:500000", alice)]).is_err());
+        assert!(parse(&[format!("{}:1000001", bob)]).is_err());
+        assert!(parse(&[bob.to_string()]).is_err());
+
+        // 收款账户需要同时指定奖励地址
+        assert!(Cli::try_parse_from(["bitnice-node", "mine", "--payee", "x:1"]).is_err());
+    }
+
     #[test]
     fn test_default_threads() {
         let mine_cmd = MineCmd {
             threads: 0,
             coinbase: None,
+            payees: vec![],
             target: None,
             verbose: false,
+            external: false,
+            stratum: None,
+            stratum_difficulty: 1000,
+            stratum_header: false,
+            bench: false,
             base: RunCmd::parse_from(&["test"]),
         };
 
@@ -2,24 +2,47 @@
 //!
 //! 处理所有命令行子命令的执行逻辑
 
-use std::sync::Arc;
-
 use crate::{
     chain_spec,
-    cli::{Cli, Subcommand},
+    cli::{Cli, MineCmd, Subcommand},
+    mining::MiningParams,
     service::{self, PartialComponents},
 };
-use bitnice_runtime::{Block, RuntimeApi};
-use sc_cli::{
-    ChainSpec, CheckBlockCmd, ExportBlocksCmd, ExportStateCmd, ImportBlocksCmd, PurgeChainCmd,
-    RevertCmd, Role, RuntimeVersion, SubstrateCli,
-};
-use sc_service::{config::PrometheusConfig, Configuration, TaskManager};
-use sp_core::crypto::Ss58AddressFormat;
+use sc_cli::{Role, SubstrateCli};
+
+impl SubstrateCli for Cli {
+    fn impl_name() -> String {
+        "BitNice Node".into()
+    }
+
+    fn impl_version() -> String {
+        env!("SUBSTRATE_CLI_IMPL_VERSION").into()
+    }
+
+    fn description() -> String {
+        "BitNice 区块链节点 - 采用SHA-256 PoW共识算法".into()
+    }
+
+    fn author() -> String {
+        env!("CARGO_PKG_AUTHORS").into()
+    }
+
+    fn support_url() -> String {
+        "https://github.com/LegendsChain/Legends/issues".into()
+    }
+
+    fn copyright_start_year() -> i32 {
+        2024
+    }
+
+    fn load_spec(&self, id: &str) -> Result<Box<dyn sc_service::ChainSpec>, String> {
+        ChainSpecLoader::load_spec(id)
+    }
+}
 
 /// 运行命令处理器
-pub async fn run() -> sc_cli::Result<()> {
-    let cli = Cli::parse();
+pub fn run() -> sc_cli::Result<()> {
+    let cli = Cli::from_args();
 
     match &cli.subcommand {
         Some(Subcommand::Key(cmd)) => {
@@ -33,7 +56,8 @@ pub async fn run() -> sc_cli::Result<()> {
         }
         Some(Subcommand::CheckBlock(cmd)) => {
             // 处理检查区块命令
-            construct_async_run!(|config| {
+            let runner = cli.create_runner(cmd)?;
+            runner.async_run(|config| {
                 let PartialComponents {
                     client,
                     task_manager,
@@ -45,7 +69,8 @@ pub async fn run() -> sc_cli::Result<()> {
         }
         Some(Subcommand::ExportBlocks(cmd)) => {
             // 处理导出区块命令
-            construct_async_run!(|config| {
+            let runner = cli.create_runner(cmd)?;
+            runner.async_run(|config| {
                 let PartialComponents {
                     client,
                     task_manager,
@@ -56,7 +81,8 @@ pub async fn run() -> sc_cli::Result<()> {
         }
         Some(Subcommand::ExportState(cmd)) => {
             // 处理导出状态命令
-            construct_async_run!(|config| {
+            let runner = cli.create_runner(cmd)?;
+            runner.async_run(|config| {
                 let PartialComponents {
                     client,
                     task_manager,
@@ -67,7 +93,8 @@ pub async fn run() -> sc_cli::Result<()> {
         }
         Some(Subcommand::ImportBlocks(cmd)) => {
             // 处理导入区块命令
-            construct_async_run!(|config| {
+            let runner = cli.create_runner(cmd)?;
+            runner.async_run(|config| {
                 let PartialComponents {
                     client,
                     task_manager,
@@ -84,17 +111,17 @@ pub async fn run() -> sc_cli::Result<()> {
         }
         Some(Subcommand::Revert(cmd)) => {
             // 处理回滚区块命令
-            construct_async_run!(|config| {
+            // PoW 辅助数据按区块哈希存储，回滚后只会留下无人引用的条目，
+            // sc-consensus-pow 也没有提供 revert，因此不需要 aux_revert
+            let runner = cli.create_runner(cmd)?;
+            runner.async_run(|config| {
                 let PartialComponents {
                     client,
                     task_manager,
                     backend,
                     ..
                 } = service::new_partial(&config)?;
-                let aux_revert = Box::new(|client, _, blocks| {
-                    sc_consensus_pow::revert(client, backend, blocks)
-                });
-                Ok((cmd.run(client, backend, Some(aux_revert)), task_manager))
+                Ok((cmd.run(client, backend, None), task_manager))
             })
         }
         #[cfg(feature = "runtime-benchmarks")]
@@ -111,57 +138,53 @@ pub async fn run() -> sc_cli::Result<()> {
             })
         }
         #[cfg(feature = "try-runtime")]
-        Some(Subcommand::TryRuntime(cmd)) => {
-            // 处理尝试运行时命令
-            use sc_executor::{sp_wasm_interface::ExtendedHostFunctions, NativeExecutionDispatch};
-            let runner = cli.create_runner(cmd)?;
-            runner.async_run(|config| {
-                let registry = config
-                    .prometheus_config
-                    .as_ref()
-                    .map(|cfg| &cfg.registry);
-                let task_manager =
-                    sc_service::TaskManager::new(config.tokio_handle.clone(), registry)?;
-                let info_provider = timestamp_with_aura_info(6000);
-                Ok((
-                    cmd.run::<Block, ExtendedHostFunctions<
-                        sp_io::SubstrateHostFunctions,
-                        <service::ExecutorDispatch as NativeExecutionDispatch>::ExtendHostFunctions,
-                    >, _>(Some(info_provider)),
-                    task_manager,
-                ))
-            })
-        }
+        Some(Subcommand::TryRuntime) => Err(
+            "try-runtime 已迁移到独立工具，请使用 https://github.com/paritytech/try-runtime-cli"
+                .into(),
+        ),
         Some(Subcommand::Mine(cmd)) => {
             // 处理挖矿命令
-            run_mining_command(cmd, &cli).await
+            run_mining_command(cmd, &cli)
         }
         None => {
             // 运行完整节点
             let runner = cli.create_runner(&cli.run)?;
-            runner
-                .run_node_until_exit(|config| async move {
-                    service::new_full(config).map_err(sc_cli::Error::Service)
-                })
-                .await
+            runner.run_node_until_exit(|config| async move {
+                service::new_full(config, None).map_err(sc_cli::Error::Service)
+            })
         }
     }
 }
 
 /// 运行挖矿命令
-async fn run_mining_command(
-    cmd: &crate::cli::MineCmd,
-    cli: &Cli,
-) -> sc_cli::Result<()> {
+fn run_mining_command(cmd: &MineCmd, cli: &Cli) -> sc_cli::Result<()> {
     use tracing::{info, warn};
 
+    // 地址格式或前缀错误时直接返回命令行错误
+    let coinbase = cmd.coinbase()?;
+
+    // 创建运行器（同时初始化日志），随后输出挖矿配置
+    let runner = cli.create_runner(&cmd.base)?;
+
+    if cmd.bench {
+        crate::mining::bench(cmd.threads());
+        return Ok(());
+    }
+
     info!("🚀 启动 BitNice 挖矿节点");
-    info!("⚡ 挖矿线程数: {}", cmd.threads());
+    if cmd.threads() == 0 {
+        info!("🌐 外部挖矿模式：只通过 RPC 或 Stratum 为外部矿工提供工作");
+    } else {
+        info!("⚡ 挖矿线程数: {}", cmd.threads());
+    }
 
     if let Some(coinbase) = cmd.coinbase_address() {
         info!("💰 奖励地址: {}", coinbase);
+        for payee in &cmd.payees {
+            info!("💸 收款账户: {}", payee);
+        }
     } else {
-        warn!("⚠️  未指定奖励地址，将使用默认地址");
+        warn!("⚠️  未指定奖励地址，挖出的区块不会产生奖励");
     }
 
     if let Some(target) = cmd.difficulty_target() {
@@ -172,43 +195,25 @@ async fn run_mining_command(
         info!("📝 启用详细日志模式");
     }
 
-    // 创建运行器并启动挖矿节点
-    let runner = cli.create_runner(&cmd.base)?;
-    runner
-        .run_node_until_exit(|mut config| async move {
-            // 确保节点以验证者身份运行（挖矿需要）
-            config.role = Role::Authority;
-
-            // 启动完整节点服务，包含挖矿功能
-            service::new_full(config).map_err(sc_cli::Error::Service)
-        })
-        .await
-}
-
-/// 时间戳信息提供者（用于 try-runtime）
-#[cfg(feature = "try-runtime")]
-fn timestamp_with_aura_info(
-    millis: u64,
-) -> impl Fn() -> Box<dyn sp_inherents::InherentDataProvider> {
-    move || {
-        let timestamp = sp_timestamp::InherentDataProvider::from_system_time();
-        Box::new(timestamp)
+    if let Some(stratum) = cmd.stratum_params() {
+        info!("🔌 Stratum 监听地址: {}，份额难度: {}", stratum.listen, stratum.share_difficulty);
     }
-}
-
-/// 构造异步运行宏
-macro_rules! construct_async_run {
-    (|$config:ident| $task:expr) => {{
-        let runner = cli.create_runner(cmd)?;
-        runner.async_run(|$config| $task).await
-    }};
-}
-
-use construct_async_run;
 
-/// 获取原生运行时版本
-pub fn get_native_runtime_version() -> &'static RuntimeVersion {
-    &bitnice_runtime::VERSION
+    let mining = MiningParams {
+        threads: cmd.threads(),
+        target: cmd.difficulty_target(),
+        verbose: cmd.is_verbose(),
+        coinbase,
+        stratum: cmd.stratum_params(),
+    };
+
+    runner.run_node_until_exit(|mut config| async move {
+        // 确保节点以验证者身份运行（挖矿需要）
+        config.role = Role::Authority;
+
+        // 启动完整节点服务，包含挖矿工作器
+        service::new_full(config, Some(mining)).map_err(sc_cli::Error::Service)
+    })
 }
 
 /// 链规范加载器
@@ -218,66 +223,14 @@ impl ChainSpecLoader {
     /// 加载链规范
     pub fn load_spec(id: &str) -> Result<Box<dyn sc_service::ChainSpec>, String> {
         Ok(match id {
-            "dev" => Box::new(chain_spec::development_config()?),
-            "" | "local" => Box::new(chain_spec::local_testnet_config()?),
+            "" | "dev" => Box::new(chain_spec::development_config()?),
+            "local" => Box::new(chain_spec::local_testnet_config()?),
             "staging" => Box::new(chain_spec::staging_testnet_config()?),
             path => Box::new(chain_spec::ChainSpec::from_json_file(
                 std::path::PathBuf::from(path),
             )?),
         })
     }
-
-    /// 获取所有可用的链规范 ID
-    pub fn available_chains() -> Vec<&'static str> {
-        vec!["dev", "local", "staging"]
-    }
-}
-
-/// 验证配置
-pub fn validate_config(config: &Configuration) -> Result<(), String> {
-    // 验证数据库配置
-    match &config.database {
-        sc_client_db::DatabaseSource::RocksDb { path, .. } => {
-            if !path.exists() {
-                std::fs::create_dir_all(path)
-                    .map_err(|e| format!("无法创建数据库目录: {}", e))?;
-            }
-        }
-        _ => {}
-    }
-
-    // 验证网络配置
-    if config.network.listen_addresses.is_empty() {
-        return Err("至少需要一个监听地址".to_string());
-    }
-
-    // 验证 RPC 配置
-    if config.rpc_methods == sc_service::config::RpcMethods::Unsafe {
-        tracing::warn!("⚠️  启用了不安全的 RPC 方法，仅用于开发环境");
-    }
-
-    Ok(())
-}
-
-/// 命令执行结果
-#[derive(Debug)]
-pub enum CommandResult {
-    /// 成功完成
-    Success,
-    /// 带有消息的成功
-    SuccessWithMessage(String),
-    /// 错误
-    Error(String),
-}
-
-impl std::fmt::Display for CommandResult {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        match self {
-            CommandResult::Success => write!(f, "命令执行成功"),
-            CommandResult::SuccessWithMessage(msg) => write!(f, "命令执行成功: {}", msg),
-            CommandResult::Error(err) => write!(f, "命令执行失败: {}", err),
-        }
-    }
 }
 
 #[cfg(test)]
@@ -290,39 +243,17 @@ mod tests {
         let spec = ChainSpecLoader::load_spec("dev");
         assert!(spec.is_ok());
 
+        // 未指定链时默认使用开发环境
+        let spec = ChainSpecLoader::load_spec("").unwrap();
+        assert_eq!(spec.id(), "bitnice_dev");
+
         // 测试本地测试网链规范加载
         let spec = ChainSpecLoader::load_spec("local");
         assert!(spec.is_ok());
 
-        // 测试预发布测试网链规范加载
-        let spec = ChainSpecLoader::load_spec("staging");
-        assert!(spec.is_ok());
-    }
-
-    #[test]
-    fn test_available_chains() {
-        let chains = ChainSpecLoader::available_chains();
-        assert!(chains.contains(&"dev"));
-        assert!(chains.contains(&"local"));
-        assert!(chains.contains(&"staging"));
-    }
-
-    #[test]
-    fn test_command_result_display() {
-        let success = CommandResult::Success;
-        assert_eq!(success.to_string(), "命令执行成功");
-
-        let success_with_msg = CommandResult::SuccessWithMessage("测试消息".to_string());
-        assert_eq!(success_with_msg.to_string(), "命令执行成功: 测试消息");
-
-        let error = CommandResult::Error("测试错误".to_string());
-        assert_eq!(error.to_string(), "命令执行失败: 测试错误");
-    }
-
-    #[test]
-    fn test_native_runtime_version() {
-        let version = get_native_runtime_version();
-        assert_eq!(version.spec_name.as_ref(), "bitnice");
-        assert_eq!(version.impl_name.as_ref(), "bitnice");
+        // 测试预发布测试网链规范加载，占位启动节点地址必须可解析
+        let spec = ChainSpecLoader::load_spec("staging").unwrap();
+        assert_eq!(spec.id(), "bitnice_staging");
+        assert_eq!(spec.boot_nodes().len(), 2);
     }
 }
@@ -0,0 +1,299 @@
+//! BitNice 挖矿线程
+//!
+//! 从 `sc_consensus_pow` 的挖矿工作器获取当前区块模板，
+//! 多线程搜索满足难度的封印，用奖励账户的 `pow_` 密钥签名后提交。每个线程分配唯一的 extranonce，
+//! 在完整的 64 位随机数空间内搜索；随机数用尽后滚动 extranonce 继续，搜索空间不会重叠或耗尽。
+//!
+//! 每个线程用 `bitnice_pow::SealHasher` 按批搜索：同一模板下的固定输入只准备一次，
+//! CPU 支持 SHA 扩展时使用硬件压缩函数，否则使用预计算中间状态的可移植实现。
+
+use std::{
+    hint::black_box,
+    thread,
+    time::{Duration, Instant},
+};
+
+use bitnice_pow::{Backend, SealHasher};
+use sp_core::H256;
+use tracing::{info, warn};
+
+use bitnice_runtime::Coinbase;
+
+use crate::{
+    pow::{extranonce, hash_meets_difficulty, Difficulty, Seal, SealSigner, LOCAL_EXTRANONCE},
+    service::MiningWorker,
+    stratum::StratumParams,
+};
+
+/// 每批尝试的随机数数量，批次之间检查模板是否更新
+const NONCES_PER_BATCH: u64 = 10_000;
+
+/// 详细模式下算力日志的输出间隔
+const HASHRATE_LOG_INTERVAL: Duration = Duration::from_secs(10);
+
+/// 没有区块模板时的等待时间
+const IDLE_WAIT: Duration = Duration::from_millis(500);
+
+/// 基准测试中每种实现的运行时间
+const BENCH_DURATION: Duration = Duration::from_secs(5);
+
+/// 挖矿参数
+#[derive(Debug, Clone)]
+pub struct MiningParams {
+    /// 搜索随机数的线程数量，为 0 时不在本地挖矿，只为外部矿工提供工作
+    pub threads: usize,
+    /// 本地难度目标，低于网络难度时无效
+    pub target: Option<u64>,
+    /// 是否输出每个线程的算力
+    pub verbose: bool,
+    /// 奖励账户与奖励分配，编码后作为 PoW 预运行时摘要写入区块
+    pub coinbase: Option<Coinbase>,
+    /// Stratum 矿池接口参数，为 `None` 时不开启
+    pub stratum: Option<StratumParams>,
+}
+
+/// 挖矿线程的搜索位置：extranonce 与下一个随机数
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NonceCursor {
+    index: u64,
+    roll: u32,
+    nonce: u64,
+}
+
+impl NonceCursor {
+    /// 第 `index` 个线程的初始位置
+    pub fn new(index: usize) -> Self {
+        Self {
+            index: index as u64,
+            roll: 0,
+            nonce: 0,
+        }
+    }
+
+    /// 当前使用的 extranonce，不同线程互不相同
+    pub fn extranonce(&self) -> u64 {
+        extranonce(LOCAL_EXTRANONCE, self.index, self.roll)
+    }
+
+    /// 取下一批最多 `size` 个随机数，返回 `(start, count, rolled)`
+    ///
+    /// 随机数用尽时滚动 extranonce 并从 0 重新开始，此时 `rolled` 为 `true`
+    pub fn next_batch(&mut self, size: u64) -> (u64, u64, bool) {
+        let rolled = self.nonce == u64::MAX;
+        if rolled {
+            self.roll = self.roll.wrapping_add(1);
+            self.nonce = 0;
+        }
+
+        let start = self.nonce;
+        let count = size.min(u64::MAX - start);
+        self.nonce += count;
+
+        (start, count, rolled)
+    }
+}
+
+/// 检测 CPU 是否支持 SHA 扩展，决定压缩函数的实现方式
+pub fn detect_backend() -> Backend {
+    #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+    if std::arch::is_x86_feature_detected!("sha") && std::arch::is_x86_feature_detected!("sse4.1") {
+        return Backend::Compress;
+    }
+
+    #[cfg(target_arch = "aarch64")]
+    if std::arch::is_aarch64_feature_detected!("sha2") {
+        return Backend::Compress;
+    }
+
+    Backend::Midstate
+}
+
+/// 启动挖矿线程
+pub fn start_miners(worker: MiningWorker, signer: SealSigner, params: MiningParams) {
+    let threads = params.threads.max(1);
+    let backend = detect_backend();
+    info!("⛏️  启动 {} 个挖矿线程，哈希实现: {:?}", threads, backend);
+
+    for index in 0..threads {
+        let worker = worker.clone();
+        let signer = signer.clone();
+        let params = params.clone();
+        let result = thread::Builder::new()
+            .name(format!("bitnice-miner-{}", index))
+            .spawn(move || mine(index, backend, worker, signer, params));
+
+        if let Err(err) = result {
+            warn!("⚠️  无法启动挖矿线程 {}: {}", index, err);
+        }
+    }
+}
+
+/// 单个挖矿线程的主循环
+fn mine(
+    index: usize,
+    backend: Backend,
+    worker: MiningWorker,
+    signer: SealSigner,
+    params: MiningParams,
+) {
+    let mut cursor = NonceCursor::new(index);
+    let mut hashes: u64 = 0;
+    let mut last_report = Instant::now();
+
+    loop {
+        let metadata = match worker.metadata() {
+            Some(metadata) => metadata,
+            None => {
+                thread::sleep(IDLE_WAIT);
+                continue;
+            }
+        };
+        let version = worker.version();
+        let difficulty = params
+            .target
+            .map(Difficulty::from)
+            .map_or(metadata.difficulty, |target| {
+                target.max(metadata.difficulty)
+            });
+
+        let target = bitnice_pow::target_words(bitnice_pow::difficulty_to_target(difficulty));
+        let mut hasher = SealHasher::new(&metadata.pre_hash, cursor.extranonce());
+
+        // 同一模板下持续搜索，直到找到封印或模板更新
+        while worker.version() == version {
+            let (start, count, rolled) = cursor.next_batch(NONCES_PER_BATCH);
+            if rolled {
+                hasher = SealHasher::new(&metadata.pre_hash, cursor.extranonce());
+            }
+            let found = hasher.search(backend, start, count, &target);
+            hashes += count;
+
+            if let Some(nonce) = found {
+                let seal = Seal::new(nonce, cursor.extranonce());
+                let seal = match signer.sign(&metadata.pre_hash, seal) {
+                    Ok(seal) => seal,
+                    Err(err) => {
+                        warn!("⚠️  线程 {} 无法为封印签名: {}", index, err);
+                        break;
+                    }
+                };
+                if futures::executor::block_on(worker.submit(seal)) {
+                    info!("✨ 线程 {} 找到封印，nonce = {}", index, nonce);
+                } else {
+                    warn!("⚠️  线程 {} 提交的封印未被接受", index);
+                }
+                break;
+            }
+
+            if params.verbose && last_report.elapsed() >= HASHRATE_LOG_INTERVAL {
+                let rate = hashes as f64 / last_report.elapsed().as_secs_f64();
+                info!("⛏️  线程 {} 算力: {:.2} kH/s", index, rate / 1000.0);
+                hashes = 0;
+                last_report = Instant::now();
+            }
+        }
+    }
+}
+
+/// 挖矿基准测试：分别测量逐个计算封印哈希与两种批量实现的算力
+pub fn bench(threads: usize) {
+    let threads = threads.max(1);
+    let backend = detect_backend();
+    info!(
+        "🏁 挖矿基准测试：{} 个线程，每项 {} 秒，CPU SHA 扩展{}",
+        threads,
+        BENCH_DURATION.as_secs(),
+        if backend == Backend::Compress {
+            "可用"
+        } else {
+            "不可用"
+        },
+    );
+
+    let baseline = measure(threads, |pre_hash, start| {
+        for nonce in start..start + NONCES_PER_BATCH {
+            let hash = bitnice_pow::compute_hash(pre_hash, &Seal::new(nonce, 0));
+            black_box(hash_meets_difficulty(&hash, Difficulty::MAX));
+        }
+    });
+    info!("📊 逐个哈希: {:.2} MH/s", baseline / 1e6);
+
+    for candidate in [Backend::Midstate, Backend::Compress] {
+        let rate = measure(threads, |pre_hash, start| {
+            // 目标为 0 时不会找到解，整批都会计算
+            let mut hasher = SealHasher::new(pre_hash, 0);
+            black_box(hasher.search(candidate, start, NONCES_PER_BATCH, &[0; 8]));
+        });
+        info!(
+            "📊 批量 {:?}: {:.2} MH/s（{:.2} 倍）{}",
+            candidate,
+            rate / 1e6,
+            rate / baseline.max(1.0),
+            if candidate == backend {
+                "，挖矿时使用"
+            } else {
+                ""
+            },
+        );
+    }
+}
+
+/// 多线程运行 `hash_batch`，返回每秒哈希次数；每次调用计算一批随机数
+fn measure(threads: usize, hash_batch: impl Fn(&H256, u64) + Sync) -> f64 {
+    let started = Instant::now();
+    let total: u64 = thread::scope(|scope| {
+        let handles: Vec<_> = (0..threads)
+            .map(|index| {
+                let hash_batch = &hash_batch;
+                scope.spawn(move || {
+                    let pre_hash = H256::repeat_byte(index as u8);
+                    let mut hashes = 0;
+                    while started.elapsed() < BENCH_DURATION {
+                        hash_batch(&pre_hash, hashes);
+                        hashes += NONCES_PER_BATCH;
+                    }
+                    hashes
+                })
+            })
+            .collect();
+
+        handles
+            .into_iter()
+            .map(|handle| handle.join().unwrap_or_default())
+            .sum()
+    });
+
+    total as f64 / started.elapsed().as_secs_f64()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_thread_extranonces_are_unique() {
+        let extranonces: std::collections::HashSet<_> =
+            (0..64).map(|i| NonceCursor::new(i).extranonce()).collect();
+
+        assert_eq!(extranonces.len(), 64);
+        assert!(extranonces.iter().all(|e| e >> 63 == 1));
+    }
+
+    #[test]
+    fn test_cursor_rolls_extranonce_on_wrap() {
+        let mut cursor = NonceCursor::new(2);
+        let initial = cursor.extranonce();
+        assert_eq!(cursor.next_batch(10), (0, 10, false));
+        assert_eq!(cursor.next_batch(10), (10, 10, false));
+
+        // 最后一批不足时截断到空间末尾
+        cursor.nonce = u64::MAX - 4;
+        assert_eq!(cursor.next_batch(10), (u64::MAX - 4, 4, false));
+        assert_eq!(cursor.extranonce(), initial);
+
+        // 随机数用尽后滚动 extranonce，从 0 重新开始
+        assert_eq!(cursor.next_batch(10), (0, 10, true));
+        assert_eq!(cursor.extranonce(), initial + 1);
+        assert_ne!(cursor.extranonce(), NonceCursor::new(3).extranonce());
+    }
+}
@@ -0,0 +1,418 @@
+//! BitNice SHA-256 工作量证明算法
+//!
+//! 实现 `sc_consensus_pow::PowAlgorithm`，封印格式与哈希规则见 `bitnice_pow`，
+//! 难度由运行时 `DifficultyApi` 提供。同时接受原生封印和 80 字节区块头模式的封印。
+//!
+//! 预运行时摘要（[`Coinbase`]：作者与可选的奖励分配）无法解码或分配超过 100% 的区块无效；
+//! 有作者的区块，封印必须带作者 `pow_` 密钥的签名；
+//! 本地线程、RPC 与 Stratum 找到的封印由 [`SealSigner`] 使用密钥库中的密钥签名后提交。
+//!
+//! 区块头模式封印的 ntime 必须等于区块 `timestamp.set` 时间戳的秒数，由 [`HeaderTimeImport`] 在导入时校验，
+//! 时间戳的漂移交给时间戳固有交易检查
+
+use std::{
+    collections::VecDeque,
+    sync::{Arc, Mutex},
+};
+
+use codec::{DecodeAll, Encode};
+use sc_consensus::{BlockCheckParams, BlockImport, BlockImportParams, ImportResult};
+use sc_consensus_pow::{Error, PowAlgorithm};
+use sp_api::ProvideRuntimeApi;
+use sp_consensus::Error as ConsensusError;
+use sp_consensus_pow::{DifficultyApi, Seal as RawSeal, POW_ENGINE_ID};
+use sp_core::{crypto::ByteArray, sr25519, Pair as _, H256, U256};
+use sp_keystore::{Error as KeystoreError, KeystorePtr};
+use sp_runtime::{
+    generic::BlockId,
+    traits::{Block as BlockT, Header as HeaderT},
+    DigestItem,
+};
+
+use bitnice_runtime::{opaque::Block, AccountId, Coinbase, RuntimeCall, TimestampCall};
+
+pub use bitnice_pow::{
+    hash_meets_difficulty, signing_payload, Seal, SignedSeal, HEADER_SEAL_VERSION, KEY_TYPE,
+};
+
+/// 难度类型（与运行时 `DifficultyApi` 保持一致），`U256` 累加的链上总工作量不会溢出
+pub type Difficulty = U256;
+
+/// RPC 矿工的 extranonce 区间
+///
+/// extranonce 按来源划分区间，保证本地线程、RPC 矿工与 Stratum 连接搜索的封印互不重叠：
+/// - Stratum：原生模式为 0（extranonce1 位于 nonce 高位），区块头模式为 `extranonce1 << 32 | extranonce2`
+/// - RPC 矿工：`1 << 62 | 编号 << 32`，低 32 位由矿工滚动
+/// - 本地线程：`1 << 63 | 线程 << 32 | 滚动次数`
+pub const RPC_EXTRANONCE: u64 = 1 << 62;
+
+/// 本地挖矿线程的 extranonce 区间
+pub const LOCAL_EXTRANONCE: u64 = 1 << 63;
+
+/// 在 `domain` 区间内为编号 `id` 生成第 `roll` 次滚动的 extranonce
+pub fn extranonce(domain: u64, id: u64, roll: u32) -> u64 {
+    domain | ((id & 0x3fff_ffff) << 32) | u64::from(roll)
+}
+
+/// 记录的挖矿模板时间戳数量
+const TEMPLATE_TIMES: usize = 8;
+
+/// 区块头模式封印的 ntime：区块时间戳（毫秒）的秒数
+pub fn header_ntime(timestamp: u64) -> u32 {
+    u32::try_from(timestamp / 1000).unwrap_or(u32::MAX)
+}
+
+/// 区块体中 `timestamp.set` 写入的时间戳（毫秒）
+pub fn block_timestamp(extrinsics: &[<Block as BlockT>::Extrinsic]) -> Option<u64> {
+    extrinsics.iter().find_map(|xt| match &xt.function {
+        RuntimeCall::Timestamp(TimestampCall::set { now }) => Some(*now),
+        _ => None,
+    })
+}
+
+/// 区块头模式封印的 ntime 是否等于区块时间戳的秒数，其他版本的封印不受限制
+pub fn ntime_matches(seal: &Seal, timestamp: Option<u64>) -> bool {
+    seal.version != HEADER_SEAL_VERSION || timestamp.map(header_ntime) == Some(seal.ntime())
+}
+
+/// 最近挖矿模板的时间戳，由挖矿工作器的固有数据提供者记录
+///
+/// 挖矿工作器在每个父区块上只构建一次模板，按父区块哈希即可找到模板中的时间戳，
+/// 区块头模式的工作据此下发 ntime
+#[derive(Clone, Default)]
+pub struct TemplateTimes(Arc<Mutex<VecDeque<(H256, u64)>>>);
+
+impl TemplateTimes {
+    /// 记录在 `parent` 上构建模板使用的时间戳（毫秒）
+    pub fn record(&self, parent: H256, timestamp: u64) {
+        let mut times = self.0.lock().expect("模板时间戳锁未被污染");
+        times.retain(|(hash, _)| *hash != parent);
+        if times.len() == TEMPLATE_TIMES {
+            times.pop_front();
+        }
+        times.push_back((parent, timestamp));
+    }
+
+    /// `parent` 上模板的 ntime，没有记录时返回 `None`
+    pub fn ntime(&self, parent: &H256) -> Option<u32> {
+        let times = self.0.lock().expect("模板时间戳锁未被污染");
+        times
+            .iter()
+            .find(|(hash, _)| hash == parent)
+            .map(|(_, timestamp)| header_ntime(*timestamp))
+    }
+}
+
+/// 用密钥库中作者的 `pow_` 密钥为封印签名
+#[derive(Clone)]
+pub struct SealSigner {
+    keystore: KeystorePtr,
+    author: Option<sr25519::Public>,
+}
+
+impl SealSigner {
+    /// 为奖励账户 `author` 创建签名器，密钥库中没有对应的 `pow_` 密钥时返回错误；
+    /// 没有奖励账户时提交不签名的封印
+    pub fn new(keystore: KeystorePtr, author: Option<&AccountId>) -> Result<Self, String> {
+        let Some(account) = author else {
+            return Ok(Self {
+                keystore,
+                author: None,
+            });
+        };
+
+        let public = sr25519::Public::from_raw(account.clone().into());
+        if !keystore.has_keys(&[(public.to_raw_vec(), KEY_TYPE)]) {
+            return Err(format!(
+                "密钥库中没有奖励账户 {} 的 pow_ 密钥，请先执行 `key insert --key-type pow_ --scheme sr25519`",
+                account
+            ));
+        }
+
+        Ok(Self {
+            keystore,
+            author: Some(public),
+        })
+    }
+
+    /// 编码提交给挖矿工作器的封印，有作者时附带签名
+    pub fn sign(&self, pre_hash: &H256, seal: Seal) -> Result<RawSeal, KeystoreError> {
+        let Some(author) = &self.author else {
+            return Ok(seal.encode());
+        };
+
+        let signature = self
+            .keystore
+            .sr25519_sign(KEY_TYPE, author, &signing_payload(pre_hash, &seal))?
+            .ok_or(KeystoreError::Unavailable)?;
+
+        Ok(SignedSeal { seal, signature }.encode())
+    }
+}
+
+/// 按预运行时摘要解码封印：有作者时要求作者签名，摘要无效、签名无效或无法解码时返回 `None`
+pub fn decode_seal(pre_hash: &H256, pre_digest: Option<&[u8]>, seal: &[u8]) -> Option<Seal> {
+    let Some(pre_digest) = pre_digest else {
+        return Seal::decode_all(&mut &seal[..]).ok();
+    };
+
+    let coinbase = Coinbase::from_digest(pre_digest)?;
+    let author = sr25519::Public::from_raw(coinbase.author.into());
+    let signed = SignedSeal::decode_all(&mut &seal[..]).ok()?;
+    let payload = signing_payload(pre_hash, &signed.seal);
+
+    sr25519::Pair::verify(&signed.signature, payload, &author).then_some(signed.seal)
+}
+
+/// BitNice SHA-256 PoW 算法
+pub struct Sha256Algorithm<C> {
+    client: Arc<C>,
+}
+
+impl<C> Sha256Algorithm<C> {
+    /// 创建新的 PoW 算法实例
+    pub fn new(client: Arc<C>) -> Self {
+        Self { client }
+    }
+}
+
+// 手动实现 Clone，避免对 C 增加 Clone 约束
+impl<C> Clone for Sha256Algorithm<C> {
+    fn clone(&self) -> Self {
+        Self::new(self.client.clone())
+    }
+}
+
+impl<B, C> PowAlgorithm<B> for Sha256Algorithm<C>
+where
+    B: BlockT<Hash = H256>,
+    C: ProvideRuntimeApi<B>,
+    C::Api: DifficultyApi<B, Difficulty>,
+{
+    type Difficulty = Difficulty;
+
+    fn difficulty(&self, parent: B::Hash) -> Result<Self::Difficulty, Error<B>> {
+        self.client
+            .runtime_api()
+            .difficulty(parent)
+            .map_err(|err| Error::Environment(format!("获取难度失败: {}", err)))
+    }
+
+    fn verify(
+        &self,
+        _parent: &BlockId<B>,
+        pre_hash: &H256,
+        pre_digest: Option<&[u8]>,
+        seal: &RawSeal,
+        difficulty: Self::Difficulty,
+    ) -> Result<bool, Error<B>> {
+        // 无法解码或签名无效的封印视为无效
+        let Some(seal) = decode_seal(pre_hash, pre_digest, seal) else {
+            return Ok(false);
+        };
+
+        Ok(bitnice_pow::verify(pre_hash, &seal, difficulty))
+    }
+}
+
+/// 校验区块头模式封印 ntime 的区块导入器，其余校验交给内部的 PoW 区块导入器
+pub struct HeaderTimeImport<I> {
+    inner: I,
+}
+
+impl<I> HeaderTimeImport<I> {
+    /// 包装 PoW 区块导入器
+    pub fn new(inner: I) -> Self {
+        Self { inner }
+    }
+}
+
+/// 待导入区块的封印 ntime 是否与区块时间戳一致
+///
+/// 封印位于 `post_digests` 末尾；无法解码的封印交给 PoW 区块导入器拒绝，
+/// 没有区块体的导入不执行区块，也不做检查
+fn header_time_matches(block: &BlockImportParams<Block>) -> bool {
+    let (Some(DigestItem::Seal(POW_ENGINE_ID, seal)), Some(body)) =
+        (block.post_digests.last(), block.body.as_deref())
+    else {
+        return true;
+    };
+
+    // 有预运行时摘要时封印带作者签名，这里只读取封印字段
+    let signed = block
+        .header
+        .digest()
+        .logs()
+        .iter()
+        .any(|log| matches!(log, DigestItem::PreRuntime(POW_ENGINE_ID, _)));
+    let seal = if signed {
+        SignedSeal::decode_all(&mut &seal[..]).map(|signed| signed.seal)
+    } else {
+        Seal::decode_all(&mut &seal[..])
+    };
+
+    match seal {
+        Ok(seal) => ntime_matches(&seal, block_timestamp(body)),
+        Err(_) => true,
+    }
+}
+
+#[async_trait::async_trait]
+impl<I> BlockImport<Block> for HeaderTimeImport<I>
+where
+    I: BlockImport<Block, Error = ConsensusError> + Send + Sync,
+{
+    type Error = ConsensusError;
+
+    async fn check_block(
+        &self,
+        block: BlockCheckParams<Block>,
+    ) -> Result<ImportResult, Self::Error> {
+        self.inner.check_block(block).await
+    }
+
+    async fn import_block(
+        &self,
+        block: BlockImportParams<Block>,
+    ) -> Result<ImportResult, Self::Error> {
+        if !header_time_matches(&block) {
+            return Err(ConsensusError::ClientImport(
+                "区块头模式封印的 ntime 与区块时间戳不一致".into(),
+            ));
+        }
+
+        self.inner.import_block(block).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_hash_meets_difficulty() {
+        // 难度为 1 时任何哈希都满足
+        assert!(hash_meets_difficulty(&H256::repeat_byte(0xff), U256::one()));
+
+        // 全 0xff 的哈希在难度为 2 时溢出
+        assert!(!hash_meets_difficulty(
+            &H256::repeat_byte(0xff),
+            U256::from(2u64)
+        ));
+
+        // 高位为 0 的哈希能满足更高的难度
+        let mut bytes = [0xffu8; 32];
+        bytes[0] = 0;
+        assert!(hash_meets_difficulty(
+            &H256::from(bytes),
+            U256::from(256u64)
+        ));
+        assert!(!hash_meets_difficulty(
+            &H256::from(bytes),
+            U256::from(257u64)
+        ));
+    }
+
+    #[test]
+    fn test_ntime_matches() {
+        let seal = Seal::header(1_700_000_000, 0, 0);
+        assert!(ntime_matches(&seal, Some(1_700_000_000_999)));
+        assert!(!ntime_matches(&seal, Some(1_700_000_001_000)));
+        assert!(!ntime_matches(&seal, None));
+
+        // 原生封印不携带时间
+        assert!(ntime_matches(&Seal::new(7, 0), None));
+    }
+
+    #[test]
+    fn test_template_times() {
+        let times = TemplateTimes::default();
+        times.record(H256::repeat_byte(1), 6_000);
+        times.record(H256::repeat_byte(2), 12_500);
+        assert_eq!(times.ntime(&H256::repeat_byte(1)), Some(6));
+        assert_eq!(times.ntime(&H256::repeat_byte(2)), Some(12));
+
+        // 只保留最近的模板
+        for parent in 3..=10 {
+            times.record(H256::repeat_byte(parent), 0);
+        }
+        assert_eq!(times.ntime(&H256::repeat_byte(1)), None);
+        assert_eq!(times.ntime(&H256::repeat_byte(10)), Some(0));
+    }
+
+    #[test]
+    fn test_extranonce_domains_do_not_overlap() {
+        let rpc = extranonce(RPC_EXTRANONCE, u64::MAX, u32::MAX);
+        let local = extranonce(LOCAL_EXTRANONCE, 0, 0);
+        assert!(rpc < local);
+        assert_eq!(rpc >> 62, 1);
+        assert_eq!(
+            extranonce(LOCAL_EXTRANONCE, 3, 5),
+            (1 << 63) | (3 << 32) | 5
+        );
+
+        // Stratum 区块头模式的 extranonce1 只有 16 位
+        assert!(u64::from(u16::MAX) << 32 | u64::from(u32::MAX) < RPC_EXTRANONCE);
+    }
+
+    #[test]
+    fn test_seal_roundtrip() {
+        let seal = Seal::new(7, 0);
+        let decoded = Seal::decode_all(&mut &seal.encode()[..]).unwrap();
+        assert_eq!(seal, decoded);
+    }
+
+    #[test]
+    fn test_signed_seal() {
+        let keystore: KeystorePtr = Arc::new(sp_keystore::testing::MemoryKeystore::new());
+        let public = keystore
+            .sr25519_generate_new(KEY_TYPE, Some("//Alice"))
+            .unwrap();
+        let author = AccountId::from(public);
+        let pre_hash = H256::repeat_byte(0x11);
+        let seal = Seal::new(7, 1);
+
+        // 没有奖励账户时提交原始封印
+        let unsigned = SealSigner::new(keystore.clone(), None)
+            .unwrap()
+            .sign(&pre_hash, seal)
+            .unwrap();
+        assert_eq!(decode_seal(&pre_hash, None, &unsigned), Some(seal));
+
+        let signed = SealSigner::new(keystore.clone(), Some(&author))
+            .unwrap()
+            .sign(&pre_hash, seal)
+            .unwrap();
+        let digest = author.encode();
+        assert_eq!(decode_seal(&pre_hash, Some(&digest), &signed), Some(seal));
+
+        // 有作者时不接受未签名的封印，签名绑定 pre_hash 与作者
+        assert_eq!(decode_seal(&pre_hash, Some(&digest), &unsigned), None);
+        assert_eq!(
+            decode_seal(&H256::repeat_byte(0x22), Some(&digest), &signed),
+            None
+        );
+        let other = AccountId::from(sr25519::Pair::from_string("//Bob", None).unwrap().public());
+        assert_eq!(decode_seal(&pre_hash, Some(&other.encode()), &signed), None);
+
+        // 密钥库中没有作者的密钥
+        assert!(SealSigner::new(keystore, Some(&other)).is_err());
+
+        // 两个收款账户各占 `percent`，合计不超过 100% 时摘要有效
+        let split = |percent| {
+            let share = sp_runtime::Permill::from_percent(percent);
+            Coinbase {
+                author: author.clone(),
+                payees: vec![(other.clone(), share), (author.clone(), share)]
+                    .try_into()
+                    .unwrap(),
+            }
+            .encode()
+        };
+        assert_eq!(
+            decode_seal(&pre_hash, Some(&split(50)), &signed),
+            Some(seal)
+        );
+        assert_eq!(decode_seal(&pre_hash, Some(&split(60)), &signed), None);
+    }
+}
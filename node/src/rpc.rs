@@ -1,61 +1,93 @@
 //! BitNice 节点 RPC 服务配置
 //!
-//! 极简版本 - 只提供基本的空RPC服务
+//! 在 `sc_service::spawn_tasks` 提供的 chain/state/author/system 标准接口之外，
+//! 合并账户 nonce（`system_accountNextIndex`）、手续费查询（`payment_*`）
+//! 与挖矿统计（`bitnice_getMiningStats`）接口；挖矿时另外开放外部矿工工作接口（`bitnice_*Work`）
+
+pub mod mining;
+pub mod work;
 
 use std::sync::Arc;
 
 use jsonrpsee::RpcModule;
+use sc_client_api::{BlockBackend, BlockchainEvents};
 use sc_transaction_pool_api::TransactionPool;
 use sp_api::ProvideRuntimeApi;
 use sp_block_builder::BlockBuilder;
 use sp_blockchain::{Error as BlockChainError, HeaderBackend, HeaderMetadata};
+use sp_consensus_pow::DifficultyApi;
 
 use bitnice_runtime::{opaque::Block, AccountId, Balance, Nonce};
 
+use crate::pow::Difficulty;
+
 /// 完整节点 RPC 依赖
 pub struct FullDeps<C, P> {
     /// 客户端引用
     pub client: Arc<C>,
     /// 交易池引用
     pub pool: Arc<P>,
-    /// 是否拒绝不安全的 RPC 调用
-    pub deny_unsafe: sc_rpc_api::DenyUnsafe,
+    /// 挖矿统计缓存，由服务中的后台任务随区块导入刷新
+    pub mining_stats: mining::MiningStatsCache<C>,
+    /// 外部矿工工作接口，仅在节点运行挖矿工作器时提供
+    pub work: Option<work::ExternalWork>,
 }
 
 /// 创建完整节点的 RPC 服务
 pub fn create_full<C, P>(
-    _deps: FullDeps<C, P>,
+    deps: FullDeps<C, P>,
 ) -> Result<RpcModule<()>, Box<dyn std::error::Error + Send + Sync>>
 where
     C: ProvideRuntimeApi<Block>,
     C: HeaderBackend<Block> + HeaderMetadata<Block, Error = BlockChainError>,
+    C: BlockBackend<Block> + BlockchainEvents<Block>,
     C: Send + Sync + 'static,
     C::Api: BlockBuilder<Block>,
     C::Api: pallet_transaction_payment_rpc::TransactionPaymentRuntimeApi<Block, Balance>,
-    C::Api: frame_system_rpc_runtime_api::AccountNonceApi<Block, AccountId, Nonce>,
+    C::Api: substrate_frame_rpc_system::AccountNonceApi<Block, AccountId, Nonce>,
+    C::Api: DifficultyApi<Block, Difficulty>,
     P: TransactionPool<Block = Block> + Send + Sync + 'static,
 {
-    // 创建空的RPC模块 - 暂时不添加任何功能
-    let io = RpcModule::new(());
-    
-    // TODO: 等API稳定后重新添加RPC功能
-    // - 系统RPC调用
-    // - 交易支付RPC调用  
-    // - 自定义BitNice RPC调用
-    
+    use pallet_transaction_payment_rpc::{TransactionPayment, TransactionPaymentApiServer};
+    use substrate_frame_rpc_system::{System, SystemApiServer};
+
+    use mining::{Mining, MiningApiServer};
+    use work::WorkApiServer;
+
+    let FullDeps {
+        client,
+        pool,
+        mining_stats,
+        work,
+    } = deps;
+
+    let mut io = RpcModule::new(());
+
+    // 账户 nonce 与交易试运行；试运行属于不安全方法，
+    // 由 RPC 服务端按连接写入的 DenyUnsafe 在每次调用时检查
+    io.merge(System::new(client.clone(), pool).into_rpc())?;
+    // 手续费查询
+    io.merge(TransactionPayment::new(client).into_rpc())?;
+    // 挖矿统计
+    io.merge(Mining::new(mining_stats).into_rpc())?;
+    // 外部矿工获取工作与提交封印
+    if let Some(work) = work {
+        io.merge(work.into_rpc())?;
+    }
+
     Ok(io)
 }
 
 /// 挖矿统计信息结构
-#[derive(serde::Serialize, serde::Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
 pub struct MiningStats {
-    /// 当前难度
-    pub current_difficulty: u64,
-    /// 网络哈希率
+    /// 当前难度（十六进制字符串）
+    pub current_difficulty: Difficulty,
+    /// 估算的全网算力（哈希/秒）
     pub network_hashrate: u64,
-    /// 区块时间（秒）
+    /// 最近区块的平均出块时间（秒）
     pub block_time: u64,
-    /// 今日挖出的区块数
+    /// 最近 24 小时挖出的区块数
     pub blocks_mined_today: u64,
 }
 
@@ -73,19 +105,38 @@ pub enum RpcError {
 
     #[error("内部错误: {0}")]
     Internal(String),
+
+    #[error("当前没有可用的挖矿工作")]
+    NoWork,
+
+    #[error("工作 {0} 已过期")]
+    StaleWork(u64),
+
+    #[error("封印无效")]
+    InvalidSeal,
 }
 
-impl From<RpcError> for jsonrpsee::core::Error {
-    fn from(error: RpcError) -> Self {
-        match error {
-            RpcError::Client(msg) => jsonrpsee::core::Error::Custom(msg),
-            RpcError::TransactionPool(msg) => jsonrpsee::core::Error::Custom(msg),
-            RpcError::Runtime(msg) => jsonrpsee::core::Error::Custom(msg),
-            RpcError::Internal(msg) => jsonrpsee::core::Error::Custom(msg),
+impl RpcError {
+    /// JSON-RPC 错误码（位于服务端自定义区间）
+    pub fn code(&self) -> i32 {
+        match self {
+            RpcError::Client(_) => 9001,
+            RpcError::TransactionPool(_) => 9002,
+            RpcError::Runtime(_) => 9003,
+            RpcError::Internal(_) => 9004,
+            RpcError::NoWork => 9005,
+            RpcError::StaleWork(_) => 9006,
+            RpcError::InvalidSeal => 9007,
         }
     }
 }
 
+impl From<RpcError> for jsonrpsee::types::ErrorObjectOwned {
+    fn from(error: RpcError) -> Self {
+        jsonrpsee::types::ErrorObject::owned(error.code(), error.to_string(), None::<()>)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -93,7 +144,7 @@ mod tests {
     #[test]
     fn test_mining_stats_serialization() {
         let stats = MiningStats {
-            current_difficulty: 1000,
+            current_difficulty: Difficulty::from(1000u64),
             network_hashrate: 1000000,
             block_time: 6,
             blocks_mined_today: 14400,
@@ -102,11 +153,11 @@ mod tests {
         // 测试序列化
         let serialized = serde_json::to_string(&stats).unwrap();
         assert!(serialized.contains("current_difficulty"));
-        assert!(serialized.contains("1000"));
+        assert!(serialized.contains("\"0x3e8\""));
 
         // 测试反序列化
         let deserialized: MiningStats = serde_json::from_str(&serialized).unwrap();
-        assert_eq!(deserialized.current_difficulty, 1000);
+        assert_eq!(deserialized.current_difficulty, Difficulty::from(1000u64));
         assert_eq!(deserialized.network_hashrate, 1000000);
         assert_eq!(deserialized.block_time, 6);
         assert_eq!(deserialized.blocks_mined_today, 14400);
@@ -115,9 +166,10 @@ mod tests {
     #[test]
     fn test_rpc_error_conversion() {
         let error = RpcError::Client("测试错误".to_string());
-        let jsonrpc_error: jsonrpsee::core::Error = error.into();
+        let jsonrpc_error: jsonrpsee::types::ErrorObjectOwned = error.into();
 
         // 确保错误能够正确转换
-        assert!(matches!(jsonrpc_error, jsonrpsee::core::Error::Custom(_)));
+        assert_eq!(jsonrpc_error.code(), 9001);
+        assert_eq!(jsonrpc_error.message(), "客户端错误: 测试错误");
     }
 }
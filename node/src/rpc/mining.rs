@@ -0,0 +1,271 @@
+//! BitNice 挖矿统计 RPC
+//!
+//! `bitnice_getMiningStats`：当前难度、平均出块时间、估算全网算力以及最近 24 小时的出块数。
+//! 时间戳取自区块中的 `timestamp.set` 固有交易。统计结果在新的最佳区块导入时刷新，
+//! RPC 调用只读取缓存，不再逐块回溯链
+
+use std::sync::{Arc, Mutex};
+
+use futures::StreamExt;
+use jsonrpsee::{core::RpcResult, proc_macros::rpc};
+use sc_client_api::{BlockBackend, BlockchainEvents};
+use sp_api::ProvideRuntimeApi;
+use sp_blockchain::HeaderBackend;
+use sp_consensus_pow::DifficultyApi;
+use sp_core::U256;
+use sp_runtime::traits::{Block as BlockT, Header as HeaderT};
+
+use bitnice_runtime::{opaque::Block, BlockNumber};
+
+use super::{MiningStats, RpcError};
+use crate::pow::{self, Difficulty};
+
+/// 计算平均出块时间使用的区块数量（与难度调整窗口一致）
+const AVERAGE_WINDOW: usize = 60;
+
+/// 一天的毫秒数
+const DAY_MILLIS: u64 = 24 * 60 * 60 * 1000;
+
+/// BitNice 挖矿 RPC 接口
+#[rpc(server, namespace = "bitnice")]
+pub trait MiningApi {
+    /// 获取挖矿统计信息
+    ///
+    /// 缓存为空时需要读取链上数据，因此在阻塞线程池中执行
+    #[method(name = "getMiningStats", blocking)]
+    fn mining_stats(&self) -> RpcResult<MiningStats>;
+}
+
+/// 挖矿统计缓存
+///
+/// 由 [`MiningStatsCache::run`] 在每个新的最佳区块导入后刷新。
+/// 每次刷新最多读取 `AVERAGE_WINDOW` 个区块体，24 小时出块数通过按高度二分查找得到
+pub struct MiningStatsCache<C> {
+    client: Arc<C>,
+    stats: Arc<Mutex<Option<MiningStats>>>,
+}
+
+impl<C> Clone for MiningStatsCache<C> {
+    fn clone(&self) -> Self {
+        Self {
+            client: self.client.clone(),
+            stats: self.stats.clone(),
+        }
+    }
+}
+
+/// 从区块体中读取 `timestamp.set` 写入的时间戳（毫秒）
+fn block_timestamp<C>(client: &C, hash: <Block as BlockT>::Hash) -> Result<Option<u64>, RpcError>
+where
+    C: BlockBackend<Block>,
+{
+    let body = client
+        .block_body(hash)
+        .map_err(|err| RpcError::Client(err.to_string()))?;
+
+    Ok(body.and_then(|extrinsics| pow::block_timestamp(&extrinsics)))
+}
+
+/// 根据难度、最近区块时间戳（从新到旧）和 24 小时出块数计算统计信息
+pub fn compute_stats(difficulty: Difficulty, recent: &[u64], blocks_today: u64) -> MiningStats {
+    let block_time_millis = match (recent.first(), recent.last()) {
+        (Some(newest), Some(oldest)) if recent.len() > 1 => {
+            newest.saturating_sub(*oldest) / (recent.len() as u64 - 1)
+        }
+        _ => 0,
+    };
+
+    // 每个区块期望的哈希次数等于难度，算力 = 难度 / 出块时间
+    let network_hashrate = if block_time_millis == 0 {
+        0
+    } else {
+        let rate = difficulty.saturating_mul(U256::from(1000u64)) / U256::from(block_time_millis);
+        u64::try_from(rate).unwrap_or(u64::MAX)
+    };
+
+    MiningStats {
+        current_difficulty: difficulty,
+        network_hashrate,
+        block_time: (block_time_millis + 500) / 1000,
+        blocks_mined_today: blocks_today,
+    }
+}
+
+/// 在 `1..=best` 中找到第一个满足 `in_window` 的高度
+///
+/// 链上时间戳单调不减，满足条件的高度是一段以 `best` 结尾的连续区间
+pub fn first_in_window<F>(best: BlockNumber, mut in_window: F) -> Result<BlockNumber, RpcError>
+where
+    F: FnMut(BlockNumber) -> Result<bool, RpcError>,
+{
+    let (mut low, mut high) = (1, best + 1);
+    while low < high {
+        let mid = low + (high - low) / 2;
+        if in_window(mid)? {
+            high = mid;
+        } else {
+            low = mid + 1;
+        }
+    }
+    Ok(low)
+}
+
+impl<C> MiningStatsCache<C>
+where
+    C: ProvideRuntimeApi<Block> + HeaderBackend<Block> + BlockBackend<Block>,
+    C: BlockchainEvents<Block>,
+    C::Api: DifficultyApi<Block, Difficulty>,
+{
+    /// 创建挖矿统计缓存
+    pub fn new(client: Arc<C>) -> Self {
+        Self {
+            client,
+            stats: Default::default(),
+        }
+    }
+
+    /// 跟随区块导入通知刷新缓存
+    ///
+    /// 刷新会同步调用运行时 API 并读取区块体，需以阻塞任务运行
+    pub async fn run(self) {
+        if let Err(err) = self.refresh(self.client.info().best_hash) {
+            tracing::debug!("挖矿统计刷新失败: {}", err);
+        }
+
+        let mut imports = self.client.import_notification_stream();
+        while let Some(notification) = imports.next().await {
+            if !notification.is_new_best {
+                continue;
+            }
+            if let Err(err) = self.refresh(notification.hash) {
+                tracing::debug!("挖矿统计刷新失败: {}", err);
+            }
+        }
+    }
+
+    /// 读取缓存的统计信息；节点启动后尚未导入新区块时先以当前最佳区块计算一次
+    pub fn get(&self) -> Result<MiningStats, RpcError> {
+        if let Some(stats) = self.stats.lock().expect("挖矿统计锁未被毒化").clone() {
+            return Ok(stats);
+        }
+        self.refresh(self.client.info().best_hash)
+    }
+
+    /// 以指定区块为链头重新计算统计信息并写入缓存
+    fn refresh(&self, best_hash: <Block as BlockT>::Hash) -> Result<MiningStats, RpcError> {
+        let stats = self.compute(best_hash)?;
+        *self.stats.lock().expect("挖矿统计锁未被毒化") = Some(stats.clone());
+        Ok(stats)
+    }
+
+    fn compute(&self, best_hash: <Block as BlockT>::Hash) -> Result<MiningStats, RpcError> {
+        let difficulty = self
+            .client
+            .runtime_api()
+            .difficulty(best_hash)
+            .map_err(|err| RpcError::Runtime(err.to_string()))?;
+
+        let header = |hash: <Block as BlockT>::Hash| {
+            self.client
+                .header(hash)
+                .map_err(|err| RpcError::Client(err.to_string()))?
+                .ok_or_else(|| RpcError::Client(format!("区块头 {hash:?} 不存在")))
+        };
+
+        // 最近的时间戳窗口；创世区块没有时间戳
+        let best_number = *header(best_hash)?.number();
+        let mut recent = Vec::with_capacity(AVERAGE_WINDOW);
+        let mut hash = best_hash;
+        let mut number = best_number;
+        while number > 0 && recent.len() < AVERAGE_WINDOW {
+            match block_timestamp(&*self.client, hash)? {
+                Some(timestamp) => recent.push(timestamp),
+                None => break,
+            }
+            hash = *header(hash)?.parent_hash();
+            number -= 1;
+        }
+
+        // 24 小时内的区块位于链头一侧，按高度二分查找窗口起点
+        let blocks_today = match recent.first() {
+            Some(&newest) => {
+                let first = first_in_window(best_number, |number| {
+                    let hash = self
+                        .client
+                        .hash(number)
+                        .map_err(|err| RpcError::Client(err.to_string()))?
+                        .ok_or_else(|| RpcError::Client(format!("区块 #{number} 不在主链上")))?;
+                    let timestamp = block_timestamp(&*self.client, hash)?.unwrap_or_default();
+                    Ok(newest.saturating_sub(timestamp) < DAY_MILLIS)
+                })?;
+                u64::from(best_number + 1 - first)
+            }
+            None => 0,
+        };
+
+        Ok(compute_stats(difficulty, &recent, blocks_today))
+    }
+}
+
+/// 挖矿 RPC 实现
+pub struct Mining<C> {
+    cache: MiningStatsCache<C>,
+}
+
+impl<C> Mining<C> {
+    /// 创建挖矿 RPC 实例
+    pub fn new(cache: MiningStatsCache<C>) -> Self {
+        Self { cache }
+    }
+}
+
+impl<C> MiningApiServer for Mining<C>
+where
+    C: ProvideRuntimeApi<Block> + HeaderBackend<Block> + BlockBackend<Block>,
+    C: BlockchainEvents<Block> + Send + Sync + 'static,
+    C::Api: DifficultyApi<Block, Difficulty>,
+{
+    fn mining_stats(&self) -> RpcResult<MiningStats> {
+        self.cache.get().map_err(Into::into)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_compute_stats() {
+        // 4 个区块，间隔 6 秒
+        let recent = [24_000, 18_000, 12_000, 6_000];
+        let stats = compute_stats(U256::from(600_000u64), &recent, 4);
+
+        assert_eq!(stats.current_difficulty, U256::from(600_000u64));
+        assert_eq!(stats.block_time, 6);
+        assert_eq!(stats.network_hashrate, 100_000);
+        assert_eq!(stats.blocks_mined_today, 4);
+    }
+
+    #[test]
+    fn test_compute_stats_without_history() {
+        let stats = compute_stats(U256::from(1000u64), &[6_000], 1);
+        assert_eq!(stats.block_time, 0);
+        assert_eq!(stats.network_hashrate, 0);
+
+        let stats = compute_stats(U256::from(1000u64), &[], 0);
+        assert_eq!(stats.blocks_mined_today, 0);
+    }
+
+    #[test]
+    fn test_first_in_window() {
+        // 第 n 个区块的时间戳为 n，窗口为时间戳不小于 7 的区块
+        let first = first_in_window(10, |number| Ok(number >= 7)).unwrap();
+        assert_eq!(first, 7);
+
+        // 所有区块都在窗口内
+        assert_eq!(first_in_window(10, |_| Ok(true)).unwrap(), 1);
+
+        // 只有链头在窗口内
+        assert_eq!(first_in_window(10, |number| Ok(number == 10)).unwrap(), 10);
+    }
+}
@@ -0,0 +1,246 @@
+//! 外部矿工工作接口
+//!
+//! - `bitnice_getWork`：返回挖矿工作器当前区块模板的预哈希、难度、紧凑目标、父区块哈希、区块头模式的 ntime 和工作编号
+//! - `bitnice_submitWork`：用奖励账户的 `pow_` 密钥为封印签名，经节点的 PoW 算法校验后通过挖矿工作器导入区块
+//! - `bitnice_subscribeWork`：最佳区块或区块模板变化时推送新的工作
+//!
+//! 工作编号即挖矿工作器的模板版本，模板更新后旧编号的提交视为过期。
+//! 每次 `getWork` 调用和每个订阅分配不同的 extranonce，矿工之间不会重复搜索
+
+use std::{
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc,
+    },
+    time::Duration,
+};
+
+use codec::DecodeAll;
+use jsonrpsee::{
+    core::{async_trait, RpcResult, SubscriptionResult},
+    proc_macros::rpc,
+    PendingSubscriptionSink, SubscriptionMessage,
+};
+use sc_consensus_pow::PowAlgorithm;
+use sp_core::{Bytes, H256};
+use sp_runtime::generic::BlockId;
+
+use bitnice_runtime::opaque::Block;
+
+use super::RpcError;
+use crate::{
+    pow::{
+        extranonce, Difficulty, Seal, SealSigner, Sha256Algorithm, TemplateTimes,
+        HEADER_SEAL_VERSION, RPC_EXTRANONCE,
+    },
+    service::{FullClient, MiningWorker},
+};
+
+/// 订阅中检查模板是否变化的间隔
+const WORK_POLL_INTERVAL: Duration = Duration::from_millis(250);
+
+/// 下发给外部矿工的工作
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct Work {
+    /// 工作编号，提交封印时原样带回
+    pub work_id: u64,
+    /// 区块预哈希（不含封印的区块头哈希）
+    pub pre_hash: H256,
+    /// 父区块哈希
+    pub parent_hash: H256,
+    /// 封印需要满足的难度
+    pub difficulty: Difficulty,
+    /// 难度对应的紧凑目标，区块头模式写入区块头的 `bits` 字段
+    pub bits: u32,
+    /// 区块头模式封印必须使用的 ntime，即模板时间戳的秒数
+    pub ntime: u32,
+    /// 分配给矿工的 extranonce，低 32 位可由矿工自行滚动
+    #[serde(default)]
+    pub extranonce: u64,
+}
+
+/// 外部矿工工作 RPC 接口
+#[rpc(server, namespace = "bitnice")]
+pub trait WorkApi {
+    /// 获取当前工作
+    #[method(name = "getWork")]
+    fn get_work(&self) -> RpcResult<Work>;
+
+    /// 提交 SCALE 编码的封印，返回区块是否成功导入
+    #[method(name = "submitWork")]
+    async fn submit_work(&self, work_id: u64, seal: Bytes) -> RpcResult<bool>;
+
+    /// 订阅新的工作
+    #[subscription(name = "subscribeWork" => "work", unsubscribe = "unsubscribeWork", item = Work)]
+    async fn subscribe_work(&self) -> SubscriptionResult;
+}
+
+/// 外部矿工工作 RPC 实现
+#[derive(Clone)]
+pub struct ExternalWork {
+    worker: MiningWorker,
+    signer: SealSigner,
+    template_times: TemplateTimes,
+    algorithm: Sha256Algorithm<FullClient>,
+    next_extranonce: Arc<AtomicU64>,
+}
+
+impl ExternalWork {
+    /// 创建工作 RPC 实例
+    pub fn new(
+        worker: MiningWorker,
+        signer: SealSigner,
+        template_times: TemplateTimes,
+        algorithm: Sha256Algorithm<FullClient>,
+    ) -> Self {
+        Self {
+            worker,
+            signer,
+            template_times,
+            algorithm,
+            next_extranonce: Default::default(),
+        }
+    }
+
+    /// 分配新的 extranonce
+    fn assign_extranonce(&self) -> u64 {
+        extranonce(
+            RPC_EXTRANONCE,
+            self.next_extranonce.fetch_add(1, Ordering::Relaxed),
+            0,
+        )
+    }
+}
+
+/// 读取挖矿工作器的当前模板，保证工作编号与模板内容一致；没有模板时间戳的模板视为没有工作
+pub fn current_work(worker: &MiningWorker, template_times: &TemplateTimes) -> Option<Work> {
+    loop {
+        let version = worker.version();
+        let metadata = worker.metadata()?;
+        let ntime = template_times.ntime(&metadata.best_hash)?;
+
+        // 读取期间模板被替换时重新读取
+        if worker.version() == version {
+            return Some(Work {
+                work_id: version as u64,
+                pre_hash: metadata.pre_hash,
+                parent_hash: metadata.best_hash,
+                difficulty: metadata.difficulty,
+                bits: bitnice_pow::difficulty_to_compact(metadata.difficulty),
+                ntime,
+                extranonce: 0,
+            });
+        }
+    }
+}
+
+/// 通过挖矿工作器提交封印并导入区块，返回是否导入成功
+///
+/// 提交过程会持有工作器内部的锁并等待区块导入，放到阻塞线程中执行，避免占用异步线程
+pub async fn submit_seal(worker: &MiningWorker, seal: Vec<u8>) -> bool {
+    let worker = worker.clone();
+    tokio::task::spawn_blocking(move || futures::executor::block_on(worker.submit(seal)))
+        .await
+        .unwrap_or(false)
+}
+
+#[async_trait]
+impl WorkApiServer for ExternalWork {
+    fn get_work(&self) -> RpcResult<Work> {
+        let work = current_work(&self.worker, &self.template_times).ok_or(RpcError::NoWork)?;
+
+        Ok(Work {
+            extranonce: self.assign_extranonce(),
+            ..work
+        })
+    }
+
+    async fn submit_work(&self, work_id: u64, seal: Bytes) -> RpcResult<bool> {
+        let metadata = self.worker.metadata().ok_or(RpcError::NoWork)?;
+        if self.worker.version() as u64 != work_id {
+            return Err(RpcError::StaleWork(work_id).into());
+        }
+
+        let seal = Seal::decode_all(&mut &seal.0[..]).map_err(|_| RpcError::InvalidSeal)?;
+        // 区块头模式的 ntime 必须与模板时间戳一致，否则区块导入时会被拒绝
+        if seal.version == HEADER_SEAL_VERSION
+            && self.template_times.ntime(&metadata.best_hash) != Some(seal.ntime())
+        {
+            return Err(RpcError::InvalidSeal.into());
+        }
+        let seal = self
+            .signer
+            .sign(&metadata.pre_hash, seal)
+            .map_err(|err| RpcError::Internal(format!("无法为封印签名: {}", err)))?;
+
+        let valid = PowAlgorithm::<Block>::verify(
+            &self.algorithm,
+            &BlockId::Hash(metadata.best_hash),
+            &metadata.pre_hash,
+            metadata.pre_runtime.as_deref(),
+            &seal,
+            metadata.difficulty,
+        )
+        .map_err(|err| RpcError::Internal(err.to_string()))?;
+        if !valid {
+            return Err(RpcError::InvalidSeal.into());
+        }
+
+        // 模板已被其他提交取走或导入失败时返回 false
+        Ok(submit_seal(&self.worker, seal).await)
+    }
+
+    async fn subscribe_work(&self, pending: PendingSubscriptionSink) -> SubscriptionResult {
+        let sink = pending.accept().await?;
+        let mut interval = tokio::time::interval(WORK_POLL_INTERVAL);
+        let mut last_work_id = None;
+        // 同一订阅的所有工作使用同一个 extranonce
+        let extranonce = self.assign_extranonce();
+
+        loop {
+            tokio::select! {
+                _ = sink.closed() => return Ok(()),
+                _ = interval.tick() => {}
+            }
+
+            // 暂无模板时等待下一次检查
+            let work = match current_work(&self.worker, &self.template_times) {
+                Some(work) => work,
+                None => continue,
+            };
+            if last_work_id == Some(work.work_id) {
+                continue;
+            }
+            last_work_id = Some(work.work_id);
+
+            let work = Work { extranonce, ..work };
+            sink.send(SubscriptionMessage::from_json(&work)?).await?;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_work_serialization() {
+        let work = Work {
+            work_id: 7,
+            pre_hash: H256::repeat_byte(0x11),
+            parent_hash: H256::repeat_byte(0x22),
+            difficulty: Difficulty::from(1_000_000u64),
+            bits: 0x1e10c6f7,
+            ntime: 1_700_000_000,
+            extranonce: RPC_EXTRANONCE,
+        };
+
+        let serialized = serde_json::to_string(&work).unwrap();
+        // 哈希以 0x 开头的十六进制字符串表示
+        assert!(serialized.contains(&format!("\"0x{}\"", "11".repeat(32))));
+        assert!(serialized.contains("\"work_id\":7"));
+
+        let deserialized: Work = serde_json::from_str(&serialized).unwrap();
+        assert_eq!(deserialized, work);
+    }
+}
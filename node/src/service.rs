@@ -1,40 +1,89 @@
 //! BitNice 节点服务配置
 //!
-//! 极简版本 - 只提供基本的节点启动功能
+//! 组装客户端、交易池、PoW 导入队列与网络服务
 
-use std::sync::Arc;
+use std::{sync::Arc, time::Duration};
 
+use codec::Encode;
+use sc_network::NetworkBackend;
 use sc_service::{error::Error as ServiceError, Configuration, TaskManager};
+use sp_inherents::CreateInherentDataProviders;
+use sp_runtime::traits::Block as BlockT;
 
 use bitnice_runtime::{self, opaque::Block, RuntimeApi};
 
-type FullClient = sc_service::TFullClient<Block, RuntimeApi, sc_executor::WasmExecutor<sp_io::SubstrateHostFunctions>>;
+use crate::{
+    mining::{self, MiningParams},
+    pow::{HeaderTimeImport, SealSigner, Sha256Algorithm, TemplateTimes},
+    rpc::{mining::MiningStatsCache, work::ExternalWork},
+    stratum::StratumServer,
+};
+
+pub type FullClient = sc_service::TFullClient<
+    Block,
+    RuntimeApi,
+    sc_executor::WasmExecutor<sp_io::SubstrateHostFunctions>,
+>;
 type FullBackend = sc_service::TFullBackend<Block>;
+type FullSelectChain = sc_consensus::LongestChain<FullBackend, Block>;
+type FullPool = sc_transaction_pool::TransactionPoolHandle<Block, FullClient>;
 
-/// 极简的组件结构
-#[allow(dead_code)]
-pub struct PartialComponents {
-    pub client: Arc<FullClient>,
-    pub backend: Arc<FullBackend>,
-    pub task_manager: TaskManager,
-}
+/// 区块导入与挖矿共用的固有数据提供者
+pub type InherentDataProviders = Box<
+    dyn CreateInherentDataProviders<Block, (), InherentDataProviders = sp_timestamp::InherentDataProvider>,
+>;
 
-/// 创建部分组件 - 极简版本
-pub fn new_partial(
-    config: &Configuration,
-) -> Result<PartialComponents, ServiceError> {
-    // 获取数据库配置
-    let db_config = sc_client_db::DatabaseSettings {
-        trie_cache_maximum_size: config.trie_cache_maximum_size,
-        state_pruning: config.state_pruning.clone(),
-        source: config.database.clone(),
-        blocks_pruning: config.blocks_pruning,
-        metrics_registry: None,  // 不使用metrics
-    };
+/// PoW 区块导入器
+pub type FullPowBlockImport = sc_consensus_pow::PowBlockImport<
+    Block,
+    Arc<FullClient>,
+    FullClient,
+    FullSelectChain,
+    Sha256Algorithm<FullClient>,
+    InherentDataProviders,
+>;
+
+/// 挖矿工作器句柄，供挖矿线程获取模板和提交封印
+pub type MiningWorker = sc_consensus_pow::MiningHandle<
+    Block,
+    Sha256Algorithm<FullClient>,
+    Arc<sc_network_sync::SyncingService<Block>>,
+    (),
+>;
+
+/// 部分组件，供完整节点和各子命令共用
+pub type PartialComponents = sc_service::PartialComponents<
+    FullClient,
+    FullBackend,
+    FullSelectChain,
+    sc_consensus::DefaultImportQueue<Block>,
+    FullPool,
+    FullPowBlockImport,
+>;
 
-    // 创建基本的数据库后端
-    let _backend = sc_service::new_db_backend::<Block>(db_config)?;
+/// 创建固有数据提供者（目前只有时间戳）
+pub fn inherent_data_providers() -> InherentDataProviders {
+    Box::new(|_parent, ()| async move {
+        Ok::<_, Box<dyn std::error::Error + Send + Sync>>(
+            sp_timestamp::InherentDataProvider::from_system_time(),
+        )
+    })
+}
+
+/// 挖矿工作器使用的固有数据提供者，记录每个模板的时间戳供区块头模式下发 ntime
+pub fn mining_inherent_data_providers(template_times: TemplateTimes) -> InherentDataProviders {
+    Box::new(move |parent, ()| {
+        let template_times = template_times.clone();
+        async move {
+            let timestamp = sp_timestamp::InherentDataProvider::from_system_time();
+            template_times.record(parent, timestamp.timestamp().as_millis());
+            Ok::<_, Box<dyn std::error::Error + Send + Sync>>(timestamp)
+        }
+    })
+}
 
+/// 创建部分组件
+pub fn new_partial(config: &Configuration) -> Result<PartialComponents, ServiceError> {
     // 创建执行器
     let wasm_executor = sc_executor::WasmExecutor::builder()
         .with_execution_method(config.executor.wasm_method)
@@ -43,36 +92,197 @@ pub fn new_partial(
         .build();
 
     // 创建客户端
-    let (client, backend, _keystore_container, task_manager) = 
-        sc_service::new_full_parts::<Block, RuntimeApi, _>(
-            config,
-            None,
-            wasm_executor,
-        )?;
-
+    let (client, backend, keystore_container, task_manager) =
+        sc_service::new_full_parts::<Block, RuntimeApi, _>(config, None, wasm_executor)?;
     let client = Arc::new(client);
 
-    Ok(PartialComponents {
+    // 最长链选择规则
+    let select_chain = sc_consensus::LongestChain::new(backend.clone());
+
+    // 交易池
+    let transaction_pool = Arc::from(
+        sc_transaction_pool::Builder::new(
+            task_manager.spawn_essential_handle(),
+            client.clone(),
+            config.role.is_authority().into(),
+        )
+        .with_options(config.transaction_pool.clone())
+        .with_prometheus(config.prometheus_registry())
+        .build(),
+    );
+
+    // PoW 区块导入：在导入前校验封印与难度
+    let algorithm = Sha256Algorithm::new(client.clone());
+    let pow_block_import = sc_consensus_pow::PowBlockImport::new(
+        client.clone(),
+        client.clone(),
+        algorithm.clone(),
+        0,
+        select_chain.clone(),
+        inherent_data_providers(),
+    );
+
+    let import_queue = sc_consensus_pow::import_queue(
+        Box::new(HeaderTimeImport::new(pow_block_import.clone())),
+        None,
+        algorithm,
+        &task_manager.spawn_essential_handle(),
+        config.prometheus_registry(),
+    )?;
+
+    Ok(sc_service::PartialComponents {
         client,
         backend,
         task_manager,
+        import_queue,
+        keystore_container,
+        select_chain,
+        transaction_pool,
+        other: pow_block_import,
     })
 }
 
-/// 创建完整节点服务 - 暂时返回任务管理器
-#[allow(dead_code)]
-pub async fn new_full(
+/// 创建完整节点服务
+///
+/// 传入 `mining` 时启动 PoW 挖矿工作器和挖矿线程，并开放外部矿工工作接口
+pub fn new_full(
     config: Configuration,
+    mining: Option<MiningParams>,
 ) -> Result<TaskManager, ServiceError> {
-    let partial = new_partial(&config)?;
+    let sc_service::PartialComponents {
+        client,
+        backend,
+        mut task_manager,
+        import_queue,
+        keystore_container,
+        select_chain,
+        transaction_pool,
+        other: pow_block_import,
+    } = new_partial(&config)?;
 
-    // TODO: 当API稳定后，创建真正的交易池和RPC服务
-    // 目前直接返回任务管理器，避免复杂的类型问题
+    type Network = sc_network::NetworkWorker<Block, <Block as BlockT>::Hash>;
+
+    let net_config = sc_network::config::FullNetworkConfiguration::<
+        Block,
+        <Block as BlockT>::Hash,
+        Network,
+    >::new(&config.network, config.prometheus_registry().cloned());
+    let metrics = Network::register_notification_metrics(config.prometheus_registry());
+
+    let (network, system_rpc_tx, tx_handler_controller, sync_service) =
+        sc_service::build_network(sc_service::BuildNetworkParams {
+            config: &config,
+            net_config,
+            client: client.clone(),
+            transaction_pool: transaction_pool.clone(),
+            spawn_handle: task_manager.spawn_handle(),
+            import_queue,
+            block_announce_validator_builder: None,
+            warp_sync_config: None,
+            block_relay: None,
+            metrics,
+        })?;
+
+    let work = mining.map(|mining| -> Result<_, ServiceError> {
+        // 奖励账户的 `pow_` 密钥为所有来源的封印签名
+        let author = mining.coinbase.as_ref().map(|coinbase| &coinbase.author);
+        let signer = SealSigner::new(keystore_container.keystore(), author)
+            .map_err(ServiceError::Other)?;
+
+        let mut proposer_factory = sc_basic_authorship::ProposerFactory::new(
+            task_manager.spawn_handle(),
+            client.clone(),
+            transaction_pool.clone(),
+            config.prometheus_registry(),
+            None,
+        );
+        // 打包上限与运行时 BlockLength 一致
+        proposer_factory.set_default_block_size_limit(bitnice_runtime::MAX_BLOCK_LENGTH as usize);
+
+        let template_times = TemplateTimes::default();
+        let (worker, worker_task) = sc_consensus_pow::start_mining_worker(
+            Box::new(HeaderTimeImport::new(pow_block_import)),
+            client.clone(),
+            select_chain,
+            Sha256Algorithm::new(client.clone()),
+            proposer_factory,
+            sync_service.clone(),
+            sync_service.clone(),
+            // 奖励账户与奖励分配写入预运行时摘要，运行时据此识别区块作者并分配奖励
+            mining.coinbase.as_ref().map(Encode::encode),
+            mining_inherent_data_providers(template_times.clone()),
+            // 等待新模板的超时时间
+            Duration::from_secs(10),
+            // 构建区块的时间上限
+            Duration::from_secs(10),
+        );
+
+        task_manager
+            .spawn_essential_handle()
+            .spawn_blocking("pow", Some("block-authoring"), worker_task);
+
+        if let Some(stratum) = mining.stratum.clone() {
+            let source = (worker.clone(), signer.clone(), template_times.clone());
+            task_manager.spawn_handle().spawn("stratum", Some("mining"), async move {
+                match StratumServer::bind(stratum).await {
+                    Ok(server) => server.run(source).await,
+                    Err(err) => tracing::error!("❌ 无法启动 Stratum 服务: {}", err),
+                }
+            });
+        }
+
+        // 线程数为 0 时只为外部矿工提供工作
+        if mining.threads > 0 {
+            mining::start_miners(worker.clone(), signer.clone(), mining);
+        }
+
+        Ok(ExternalWork::new(
+            worker,
+            signer,
+            template_times,
+            Sha256Algorithm::new(client.clone()),
+        ))
+    })
+    .transpose()?;
+
+    // 挖矿统计随新的最佳区块刷新，RPC 只读取缓存；刷新需读取链上数据，放在阻塞线程中运行
+    let mining_stats = MiningStatsCache::new(client.clone());
+    task_manager
+        .spawn_handle()
+        .spawn_blocking("mining-stats", Some("rpc"), mining_stats.clone().run());
+
+    let rpc_builder = {
+        let client = client.clone();
+        let pool = transaction_pool.clone();
+
+        Box::new(move |_| {
+            let deps = crate::rpc::FullDeps {
+                client: client.clone(),
+                pool: pool.clone(),
+                mining_stats: mining_stats.clone(),
+                work: work.clone(),
+            };
+            crate::rpc::create_full(deps).map_err(Into::into)
+        })
+    };
+
+    // spawn_tasks 会合并 chain/state/author/system 等标准 RPC
+    sc_service::spawn_tasks(sc_service::SpawnTasksParams {
+        network,
+        client,
+        keystore: keystore_container.keystore(),
+        task_manager: &mut task_manager,
+        transaction_pool,
+        rpc_builder,
+        backend,
+        system_rpc_tx,
+        tx_handler_controller,
+        sync_service,
+        config,
+        telemetry: None,
+    })?;
 
-    println!("✅ BitNice 节点启动成功!");
-    println!("⚠️  注意：当前是极简模式，未包含RPC和交易池功能");
-    
-    Ok(partial.task_manager)
+    Ok(task_manager)
 }
 
 #[cfg(test)]
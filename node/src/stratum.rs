@@ -0,0 +1,1030 @@
+//! BitNice Stratum v1 矿池接口
+//!
+//! 在 TCP 上提供以换行分隔的 JSON-RPC，支持 `mining.subscribe`、`mining.authorize`、
+//! `mining.submit`，并向矿工推送 `mining.set_difficulty` 与 `mining.notify`。
+//!
+//! 每个连接分配一个未被占用的 2 字节 extranonce1（断开后回收，全部占用时拒绝新连接），矿工自选 2 字节的 extranonce2 和 4 字节的 nonce，
+//! 组合成封印中的 64 位随机数：
+//!
+//! ```text
+//! nonce = extranonce1 << 48 | extranonce2 << 32 | nonce32
+//! ```
+//!
+//! 份额按 Stratum 难度校验并按矿工统计，同时满足区块难度的份额通过挖矿工作器导入区块。
+//!
+//! 开启区块头模式后按比特币 Stratum 格式下发工作，SHA-256d 矿机固件可以直接挖矿：
+//! `pre_hash` 作为上一区块哈希，coinbase 只包含 4 字节 extranonce1 与 4 字节 extranonce2，
+//! 提交的 `[worker, job_id, extranonce2, ntime, nonce]` 组成版本 2 的封印（见 `bitnice_pow`）。
+//! ntime 固定为区块模板时间戳的秒数，不允许滚动。
+//!
+//! 满足区块难度的份额由节点用奖励账户的 `pow_` 密钥签名后提交，矿机无需持有密钥。
+
+use std::{
+    collections::{HashMap, HashSet},
+    io,
+    net::SocketAddr,
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+
+use futures::future::BoxFuture;
+use serde_json::{json, Value};
+use sp_core::H256;
+use tokio::{
+    io::{AsyncBufRead, AsyncBufReadExt, AsyncReadExt, AsyncWrite, AsyncWriteExt, BufReader},
+    net::{TcpListener, TcpStream},
+    sync::watch,
+};
+use tracing::{debug, info, warn};
+
+use crate::{
+    pow::{hash_meets_difficulty, Difficulty, Seal, SealSigner, TemplateTimes},
+    rpc::work::{current_work, submit_seal, Work},
+    service::MiningWorker,
+};
+
+/// 矿工自选的 extranonce2 字节数
+const EXTRANONCE2_SIZE: usize = 2;
+
+/// 区块头模式下 extranonce1 与 extranonce2 的字节数
+const HEADER_EXTRANONCE1_SIZE: usize = 4;
+const HEADER_EXTRANONCE2_SIZE: usize = 4;
+
+/// 单行请求的最大字节数，超过时断开连接
+const MAX_LINE_LENGTH: usize = 4096;
+
+/// 检查区块模板是否变化的间隔
+const JOB_POLL_INTERVAL: Duration = Duration::from_millis(250);
+
+/// 输出矿工份额统计的间隔
+const STATS_LOG_INTERVAL: Duration = Duration::from_secs(60);
+
+/// Stratum 错误码
+const ERR_OTHER: i64 = 20;
+const ERR_JOB_NOT_FOUND: i64 = 21;
+const ERR_DUPLICATE_SHARE: i64 = 22;
+const ERR_LOW_DIFFICULTY: i64 = 23;
+const ERR_UNAUTHORIZED: i64 = 24;
+const ERR_NOT_SUBSCRIBED: i64 = 25;
+
+/// Stratum 服务参数
+#[derive(Debug, Clone)]
+pub struct StratumParams {
+    /// 监听地址
+    pub listen: SocketAddr,
+    /// 份额难度，高于区块难度时按区块难度下发
+    pub share_difficulty: Difficulty,
+    /// 以比特币 80 字节区块头模式下发工作，兼容 SHA-256d 矿机
+    pub header_mode: bool,
+}
+
+/// Stratum 服务的工作来源
+pub trait WorkSource: Clone + Send + Sync + 'static {
+    /// 当前工作，没有区块模板时返回 `None`
+    fn work(&self) -> Option<Work>;
+
+    /// 提交满足区块难度的封印，返回区块是否导入成功
+    fn submit(&self, pre_hash: H256, seal: Seal) -> BoxFuture<'static, bool>;
+}
+
+/// 挖矿工作器作为工作来源，封印用奖励账户的 `pow_` 密钥签名后提交
+impl WorkSource for (MiningWorker, SealSigner, TemplateTimes) {
+    fn work(&self) -> Option<Work> {
+        current_work(&self.0, &self.2)
+    }
+
+    fn submit(&self, pre_hash: H256, seal: Seal) -> BoxFuture<'static, bool> {
+        let (worker, signer, _) = self.clone();
+        Box::pin(async move {
+            match signer.sign(&pre_hash, seal) {
+                Ok(seal) => submit_seal(&worker, seal).await,
+                Err(err) => {
+                    warn!("⚠️  无法为 Stratum 封印签名: {}", err);
+                    false
+                }
+            }
+        })
+    }
+}
+
+/// 单个矿工的份额统计
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ShareStats {
+    /// 接受的份额
+    pub accepted: u64,
+    /// 拒绝的份额
+    pub rejected: u64,
+    /// 挖出的区块
+    pub blocks: u64,
+}
+
+/// 按矿工名称记录的份额统计
+pub type WorkerStats = Arc<Mutex<HashMap<String, ShareStats>>>;
+
+/// 更新矿工的份额统计
+fn record(stats: &WorkerStats, worker: &str, update: impl FnOnce(&mut ShareStats)) {
+    let mut stats = stats.lock().expect("份额统计锁未被污染");
+    update(stats.entry(worker.to_string()).or_default());
+}
+
+/// 由 extranonce1、extranonce2 和矿工的 32 位 nonce 组合出封印随机数
+pub fn compose_nonce(extranonce1: u16, extranonce2: u16, nonce: u32) -> u64 {
+    (u64::from(extranonce1) << 48) | (u64::from(extranonce2) << 32) | u64::from(nonce)
+}
+
+/// 难度的 JSON 表示，超出 u64 范围时使用浮点数
+fn difficulty_value(difficulty: Difficulty) -> Value {
+    u64::try_from(difficulty).map_or_else(|_| Value::from(difficulty_f64(difficulty)), Value::from)
+}
+
+/// 难度的浮点近似值
+fn difficulty_f64(difficulty: Difficulty) -> f64 {
+    difficulty
+        .0
+        .iter()
+        .rev()
+        .fold(0f64, |acc, limb| acc * 2f64.powi(64) + *limb as f64)
+}
+
+/// 换算为比特币 Stratum 难度：比特币难度 1 对应目标 `0xffff << 208`
+fn bitcoin_difficulty(difficulty: Difficulty) -> f64 {
+    difficulty_f64(difficulty) * f64::from(0xffff) / 2f64.powi(48)
+}
+
+/// 比特币 Stratum 的 prevhash：区块头中的字节按 4 字节一组逆序
+fn stratum_prevhash(hash: &H256) -> String {
+    let mut bytes = hash.0;
+    bytes.chunks_mut(4).for_each(|word| word.reverse());
+    hex::encode(bytes)
+}
+
+/// Stratum 错误对象 `[code, message, null]`
+fn stratum_error(code: i64, message: &str) -> Value {
+    json!([code, message, Value::Null])
+}
+
+/// Stratum v1 服务
+pub struct StratumServer {
+    listener: TcpListener,
+    params: StratumParams,
+    stats: WorkerStats,
+    extranonces: Extranonces,
+}
+
+impl StratumServer {
+    /// 绑定监听地址
+    pub async fn bind(params: StratumParams) -> io::Result<Self> {
+        let listener = TcpListener::bind(params.listen).await?;
+
+        Ok(Self {
+            listener,
+            params,
+            stats: Default::default(),
+            extranonces: Default::default(),
+        })
+    }
+
+    /// 实际监听的地址
+    pub fn local_addr(&self) -> io::Result<SocketAddr> {
+        self.listener.local_addr()
+    }
+
+    /// 矿工份额统计
+    pub fn stats(&self) -> WorkerStats {
+        self.stats.clone()
+    }
+
+    /// 接受矿工连接，直到任务被取消
+    pub async fn run<S: WorkSource>(self, source: S) {
+        info!(
+            "⛏️  Stratum 服务监听 {}，份额难度 {}{}",
+            self.local_addr().unwrap_or(self.params.listen),
+            self.params.share_difficulty,
+            if self.params.header_mode {
+                "，区块头模式"
+            } else {
+                ""
+            },
+        );
+
+        let (jobs_tx, jobs_rx) = watch::channel(source.work());
+        tokio::spawn(poll_jobs(source.clone(), jobs_tx));
+        tokio::spawn(log_stats(self.stats.clone()));
+
+        loop {
+            let (stream, peer) = match self.listener.accept().await {
+                Ok(connection) => connection,
+                Err(err) => {
+                    warn!("⚠️  接受 Stratum 连接失败: {}", err);
+                    continue;
+                }
+            };
+
+            // 所有 extranonce1 都被占用时拒绝连接，避免两个连接搜索同一段随机数
+            let Some(extranonce1) = self.extranonces.acquire() else {
+                warn!("⚠️  extranonce1 已全部占用，拒绝 Stratum 连接 {}", peer);
+                continue;
+            };
+            let session = Session {
+                extranonce1: extranonce1.value,
+                share_difficulty: self.params.share_difficulty,
+                header_mode: self.params.header_mode,
+                worker: None,
+                subscribed: false,
+                job: None,
+                sent_difficulty: None,
+                submitted: HashSet::new(),
+            };
+            let source = source.clone();
+            let stats = self.stats.clone();
+            let jobs = jobs_rx.clone();
+
+            tokio::spawn(async move {
+                debug!(
+                    "Stratum 连接 {}，extranonce1 = {:04x}",
+                    peer, session.extranonce1
+                );
+                if let Err(err) = session.run(stream, jobs, source, stats).await {
+                    debug!("Stratum 连接 {} 断开: {}", peer, err);
+                }
+                drop(extranonce1);
+            });
+        }
+    }
+}
+
+/// extranonce1 分配器，记录正在使用的值
+#[derive(Clone, Default)]
+struct Extranonces {
+    state: Arc<Mutex<ExtranonceState>>,
+}
+
+#[derive(Default)]
+struct ExtranonceState {
+    next: u16,
+    in_use: HashSet<u16>,
+}
+
+impl Extranonces {
+    /// 分配一个未被占用的 extranonce1，全部占用时返回 `None`
+    fn acquire(&self) -> Option<Extranonce1> {
+        let mut state = self.state.lock().expect("extranonce 锁未被污染");
+        if state.in_use.len() > usize::from(u16::MAX) {
+            return None;
+        }
+
+        loop {
+            let value = state.next;
+            state.next = value.wrapping_add(1);
+            if state.in_use.insert(value) {
+                return Some(Extranonce1 {
+                    value,
+                    extranonces: self.clone(),
+                });
+            }
+        }
+    }
+}
+
+/// 已分配的 extranonce1，释放时归还分配器
+struct Extranonce1 {
+    value: u16,
+    extranonces: Extranonces,
+}
+
+impl Drop for Extranonce1 {
+    fn drop(&mut self) {
+        self.extranonces
+            .state
+            .lock()
+            .expect("extranonce 锁未被污染")
+            .in_use
+            .remove(&self.value);
+    }
+}
+
+/// 轮询工作来源，模板变化时通知所有连接
+async fn poll_jobs<S: WorkSource>(source: S, jobs: watch::Sender<Option<Work>>) {
+    let mut interval = tokio::time::interval(JOB_POLL_INTERVAL);
+
+    loop {
+        interval.tick().await;
+
+        let work = source.work();
+        jobs.send_if_modified(|current| {
+            let changed = *current != work;
+            *current = work;
+            changed
+        });
+        if jobs.is_closed() {
+            return;
+        }
+    }
+}
+
+/// 定期输出每个矿工的份额统计
+async fn log_stats(stats: WorkerStats) {
+    let mut interval = tokio::time::interval(STATS_LOG_INTERVAL);
+    // 第一次 tick 立即完成，跳过
+    interval.tick().await;
+
+    loop {
+        interval.tick().await;
+
+        let snapshot = stats.lock().expect("份额统计锁未被污染").clone();
+        for (worker, share) in snapshot {
+            info!(
+                "📊 矿工 {} 份额: 接受 {}，拒绝 {}，区块 {}",
+                worker, share.accepted, share.rejected, share.blocks
+            );
+        }
+    }
+}
+
+/// 矿工请求
+#[derive(Debug, serde::Deserialize)]
+struct Request {
+    #[serde(default)]
+    id: Value,
+    method: String,
+    #[serde(default)]
+    params: Vec<Value>,
+}
+
+/// 单个矿工连接的状态
+struct Session {
+    extranonce1: u16,
+    share_difficulty: Difficulty,
+    header_mode: bool,
+    worker: Option<String>,
+    subscribed: bool,
+    job: Option<Work>,
+    sent_difficulty: Option<Difficulty>,
+    /// 已接受份额的 `(nonce, extranonce)`，用于拒绝重复提交
+    submitted: HashSet<(u64, u64)>,
+}
+
+impl Session {
+    /// 处理连接上的请求与工作推送
+    async fn run<S: WorkSource>(
+        mut self,
+        stream: TcpStream,
+        mut jobs: watch::Receiver<Option<Work>>,
+        source: S,
+        stats: WorkerStats,
+    ) -> io::Result<()> {
+        let (reader, mut writer) = stream.into_split();
+        let mut reader = BufReader::new(reader);
+        let mut buf = Vec::new();
+
+        loop {
+            tokio::select! {
+                line = read_line(&mut reader, &mut buf) => {
+                    let line = match line? {
+                        Some(line) => line,
+                        None => return Ok(()),
+                    };
+                    if line.trim().is_empty() {
+                        continue;
+                    }
+
+                    let request: Request = match serde_json::from_str(&line) {
+                        Ok(request) => request,
+                        Err(err) => {
+                            let error = stratum_error(ERR_OTHER, &format!("无法解析请求: {}", err));
+                            let response = json!({ "id": Value::Null, "result": Value::Null, "error": error });
+                            send(&mut writer, &response).await?;
+                            continue;
+                        }
+                    };
+
+                    let was_subscribed = self.subscribed;
+                    let response = self.handle(request, &source, &stats).await;
+                    send(&mut writer, &response).await?;
+
+                    // 订阅成功后立即下发难度和当前工作
+                    if !was_subscribed && self.subscribed {
+                        let job = jobs.borrow_and_update().clone();
+                        if let Some(job) = job {
+                            self.notify(&mut writer, job).await?;
+                        }
+                    }
+                }
+                changed = jobs.changed() => {
+                    // 服务停止
+                    if changed.is_err() {
+                        return Ok(());
+                    }
+
+                    let job = jobs.borrow_and_update().clone();
+                    match job {
+                        Some(job) if self.subscribed => self.notify(&mut writer, job).await?,
+                        Some(_) => {}
+                        // 模板被取走后旧工作不再有效
+                        None => self.job = None,
+                    }
+                }
+            }
+        }
+    }
+
+    /// 处理单个请求，返回响应
+    async fn handle<S: WorkSource>(
+        &mut self,
+        request: Request,
+        source: &S,
+        stats: &WorkerStats,
+    ) -> Value {
+        let result = match request.method.as_str() {
+            "mining.subscribe" => Ok(self.subscribe()),
+            "mining.authorize" => self.authorize(&request.params),
+            "mining.submit" => self.submit(&request.params, source, stats).await,
+            // 兼容部分矿机在订阅前发送的扩展协商
+            "mining.configure" | "mining.extranonce.subscribe" => Ok(Value::Bool(false)),
+            method => Err(stratum_error(
+                ERR_OTHER,
+                &format!("不支持的方法 {}", method),
+            )),
+        };
+
+        match result {
+            Ok(result) => json!({ "id": request.id, "result": result, "error": Value::Null }),
+            Err(error) => json!({ "id": request.id, "result": Value::Null, "error": error }),
+        }
+    }
+
+    fn subscribe(&mut self) -> Value {
+        self.subscribed = true;
+        let subscription = format!("{:04x}", self.extranonce1);
+        let (extranonce1, extranonce2_size) = if self.header_mode {
+            (
+                format!(
+                    "{:0width$x}",
+                    self.extranonce1,
+                    width = HEADER_EXTRANONCE1_SIZE * 2
+                ),
+                HEADER_EXTRANONCE2_SIZE,
+            )
+        } else {
+            (format!("{:04x}", self.extranonce1), EXTRANONCE2_SIZE)
+        };
+
+        json!([
+            [
+                ["mining.set_difficulty", subscription],
+                ["mining.notify", subscription],
+            ],
+            extranonce1,
+            extranonce2_size,
+        ])
+    }
+
+    fn authorize(&mut self, params: &[Value]) -> Result<Value, Value> {
+        let worker = params
+            .first()
+            .and_then(Value::as_str)
+            .filter(|worker| !worker.is_empty())
+            .ok_or_else(|| stratum_error(ERR_OTHER, "缺少矿工名称"))?;
+
+        info!("👷 Stratum 矿工 {} 已登录", worker);
+        self.worker = Some(worker.to_string());
+
+        Ok(Value::Bool(true))
+    }
+
+    async fn submit<S: WorkSource>(
+        &mut self,
+        params: &[Value],
+        source: &S,
+        stats: &WorkerStats,
+    ) -> Result<Value, Value> {
+        if !self.subscribed {
+            return Err(stratum_error(ERR_NOT_SUBSCRIBED, "Not subscribed"));
+        }
+        let worker = self
+            .worker
+            .clone()
+            .ok_or_else(|| stratum_error(ERR_UNAUTHORIZED, "Unauthorized worker"))?;
+
+        let result = self.check_share(params);
+        let (job, seal) = match result {
+            Ok(share) => share,
+            Err(error) => {
+                record(stats, &worker, |share| share.rejected += 1);
+                return Err(error);
+            }
+        };
+        record(stats, &worker, |share| share.accepted += 1);
+        debug!("矿工 {} 提交份额，nonce = {}", worker, seal.nonce);
+
+        if bitnice_pow::verify(&job.pre_hash, &seal, job.difficulty) {
+            if source.submit(job.pre_hash, seal).await {
+                record(stats, &worker, |share| share.blocks += 1);
+                info!(
+                    "✨ Stratum 矿工 {} 找到封印，nonce = {}",
+                    worker, seal.nonce
+                );
+            } else {
+                warn!("⚠️  Stratum 矿工 {} 提交的封印未被接受", worker);
+            }
+        }
+
+        Ok(Value::Bool(true))
+    }
+
+    /// 校验份额：工作编号、重复提交与份额难度
+    fn check_share(&mut self, params: &[Value]) -> Result<(Work, Seal), Value> {
+        let param = |index: usize| params.get(index).and_then(Value::as_str);
+        let hex32 = |index: usize, size: usize| {
+            param(index)
+                .filter(|hex| hex.len() == size * 2)
+                .and_then(|hex| u32::from_str_radix(hex, 16).ok())
+        };
+        let invalid = || {
+            let format = if self.header_mode {
+                "参数格式应为 [worker, job_id, extranonce2, ntime, nonce]"
+            } else {
+                "参数格式应为 [worker, job_id, extranonce2, nonce]"
+            };
+            stratum_error(ERR_OTHER, format)
+        };
+
+        let job_id = param(1).ok_or_else(invalid)?;
+        let seal = if self.header_mode {
+            let extranonce2 = hex32(2, HEADER_EXTRANONCE2_SIZE).ok_or_else(invalid)?;
+            let ntime = hex32(3, 4).ok_or_else(invalid)?;
+            let nonce = hex32(4, 4).ok_or_else(invalid)?;
+            let extranonce = (u64::from(self.extranonce1) << 32) | u64::from(extranonce2);
+            Seal::header(ntime, nonce, extranonce)
+        } else {
+            let extranonce2 = hex32(2, EXTRANONCE2_SIZE).ok_or_else(invalid)?;
+            let nonce = hex32(3, 4).ok_or_else(invalid)?;
+            Seal::new(
+                compose_nonce(self.extranonce1, extranonce2 as u16, nonce),
+                0,
+            )
+        };
+
+        let job = match &self.job {
+            Some(job) if format!("{:x}", job.work_id) == job_id => job.clone(),
+            _ => return Err(stratum_error(ERR_JOB_NOT_FOUND, "Job not found")),
+        };
+
+        // ntime 必须是模板时间戳，区块导入时按区块的 `timestamp.set` 校验
+        if self.header_mode && seal.ntime() != job.ntime {
+            return Err(stratum_error(ERR_OTHER, "Invalid ntime"));
+        }
+
+        let key = (seal.nonce, seal.extranonce);
+        if self.submitted.contains(&key) {
+            return Err(stratum_error(ERR_DUPLICATE_SHARE, "Duplicate share"));
+        }
+
+        let difficulty = self.job_difficulty(&job);
+        let meets = if self.header_mode {
+            let hash = bitnice_pow::compute_header_hash(&job.pre_hash, &seal, job.bits);
+            bitnice_pow::header_hash_meets_difficulty(&hash, difficulty)
+        } else {
+            hash_meets_difficulty(&bitnice_pow::compute_hash(&job.pre_hash, &seal), difficulty)
+        };
+        if !meets {
+            return Err(stratum_error(ERR_LOW_DIFFICULTY, "Low difficulty share"));
+        }
+
+        // 只记录有效份额，无效份额不占用去重集合
+        self.submitted.insert(key);
+        Ok((job, seal))
+    }
+
+    /// 份额难度的 JSON 表示，区块头模式换算为比特币难度
+    fn difficulty_value(&self, difficulty: Difficulty) -> Value {
+        if self.header_mode {
+            Value::from(bitcoin_difficulty(difficulty))
+        } else {
+            difficulty_value(difficulty)
+        }
+    }
+
+    /// 下发给矿工的份额难度，不超过区块难度
+    fn job_difficulty(&self, job: &Work) -> Difficulty {
+        self.share_difficulty
+            .min(job.difficulty)
+            .max(Difficulty::one())
+    }
+
+    /// 推送新工作，份额难度变化时先推送难度
+    async fn notify<W: AsyncWrite + Unpin>(&mut self, writer: &mut W, job: Work) -> io::Result<()> {
+        let difficulty = self.job_difficulty(&job);
+        if self.sent_difficulty != Some(difficulty) {
+            self.sent_difficulty = Some(difficulty);
+            send(
+                writer,
+                &json!({
+                    "id": Value::Null,
+                    "method": "mining.set_difficulty",
+                    "params": [self.difficulty_value(difficulty)],
+                }),
+            )
+            .await?;
+        }
+
+        // 每个模板都替换之前的工作
+        let params = if self.header_mode {
+            json!([
+                format!("{:x}", job.work_id),
+                stratum_prevhash(&job.pre_hash),
+                "",
+                "",
+                [],
+                format!("{:08x}", bitnice_pow::HEADER_VERSION),
+                format!("{:08x}", job.bits),
+                format!("{:08x}", job.ntime),
+                true,
+            ])
+        } else {
+            json!([
+                format!("{:x}", job.work_id),
+                hex::encode(job.pre_hash.as_bytes()),
+                hex::encode(job.parent_hash.as_bytes()),
+                true,
+            ])
+        };
+        self.submitted.clear();
+        self.job = Some(job);
+
+        send(
+            writer,
+            &json!({ "id": Value::Null, "method": "mining.notify", "params": params }),
+        )
+        .await
+    }
+}
+
+/// 读取一行请求，连接关闭时返回 `None`
+///
+/// 单行超过 `MAX_LINE_LENGTH` 字节时返回错误。未读完的字节保留在 `buf` 中，
+/// 在 `select!` 中被取消后再次调用会继续读取同一行
+async fn read_line<R: AsyncBufRead + Unpin>(
+    reader: &mut R,
+    buf: &mut Vec<u8>,
+) -> io::Result<Option<String>> {
+    let limit = (MAX_LINE_LENGTH + 1).saturating_sub(buf.len()) as u64;
+    (&mut *reader).take(limit).read_until(b'\n', buf).await?;
+
+    if buf.last() != Some(&b'\n') {
+        if buf.len() > MAX_LINE_LENGTH {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("请求行超过 {} 字节", MAX_LINE_LENGTH),
+            ));
+        }
+        // 连接关闭，最后一行可以没有换行符
+        if buf.is_empty() {
+            return Ok(None);
+        }
+    }
+
+    let mut line = std::mem::take(buf);
+    if line.last() == Some(&b'\n') {
+        line.pop();
+        if line.last() == Some(&b'\r') {
+            line.pop();
+        }
+    }
+    String::from_utf8(line)
+        .map(Some)
+        .map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))
+}
+
+/// 发送一行 JSON 消息
+async fn send<W: AsyncWrite + Unpin>(writer: &mut W, message: &Value) -> io::Result<()> {
+    let mut line = message.to_string();
+    line.push('\n');
+    writer.write_all(line.as_bytes()).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sp_core::U256;
+    use tokio::{io::Lines, net::tcp::OwnedReadHalf};
+
+    /// 固定工作的测试来源，记录提交的封印
+    #[derive(Clone)]
+    struct TestSource {
+        work: Work,
+        submitted: Arc<Mutex<Vec<Seal>>>,
+    }
+
+    impl TestSource {
+        fn new(difficulty: Difficulty) -> Self {
+            Self {
+                work: Work {
+                    work_id: 1,
+                    pre_hash: H256::repeat_byte(0x11),
+                    parent_hash: H256::repeat_byte(0x22),
+                    difficulty,
+                    bits: bitnice_pow::difficulty_to_compact(difficulty),
+                    ntime: 1_700_000_000,
+                    extranonce: 0,
+                },
+                submitted: Default::default(),
+            }
+        }
+    }
+
+    impl WorkSource for TestSource {
+        fn work(&self) -> Option<Work> {
+            Some(self.work.clone())
+        }
+
+        fn submit(&self, _pre_hash: H256, seal: Seal) -> BoxFuture<'static, bool> {
+            self.submitted.lock().unwrap().push(seal);
+            Box::pin(async { true })
+        }
+    }
+
+    /// 模拟矿机的 TCP 客户端
+    struct TestClient {
+        lines: Lines<BufReader<OwnedReadHalf>>,
+        writer: tokio::net::tcp::OwnedWriteHalf,
+        next_id: u64,
+    }
+
+    impl TestClient {
+        async fn connect(addr: SocketAddr) -> Self {
+            let (reader, writer) = TcpStream::connect(addr).await.unwrap().into_split();
+            Self {
+                lines: BufReader::new(reader).lines(),
+                writer,
+                next_id: 1,
+            }
+        }
+
+        async fn next(&mut self) -> Value {
+            let line = self.lines.next_line().await.unwrap().unwrap();
+            serde_json::from_str(&line).unwrap()
+        }
+
+        async fn call(&mut self, method: &str, params: Value) -> Value {
+            let id = self.next_id;
+            self.next_id += 1;
+            send(
+                &mut self.writer,
+                &json!({ "id": id, "method": method, "params": params }),
+            )
+            .await
+            .unwrap();
+
+            let response = self.next().await;
+            assert_eq!(response["id"], id);
+            response
+        }
+    }
+
+    async fn start(
+        source: TestSource,
+        share_difficulty: Difficulty,
+        header_mode: bool,
+    ) -> (SocketAddr, WorkerStats) {
+        let server = StratumServer::bind(StratumParams {
+            listen: "127.0.0.1:0".parse().unwrap(),
+            share_difficulty,
+            header_mode,
+        })
+        .await
+        .unwrap();
+        let addr = server.local_addr().unwrap();
+        let stats = server.stats();
+        tokio::spawn(server.run(source));
+
+        (addr, stats)
+    }
+
+    #[test]
+    fn test_compose_nonce() {
+        assert_eq!(compose_nonce(0, 0, 0), 0);
+        assert_eq!(compose_nonce(1, 0, 0), 1 << 48);
+        assert_eq!(compose_nonce(0, 1, 0), 1 << 32);
+        assert_eq!(compose_nonce(0xffff, 0xffff, u32::MAX), u64::MAX);
+    }
+
+    #[test]
+    fn test_extranonce1_reuse() {
+        let extranonces = Extranonces::default();
+        let mut leases: Vec<_> = (0..=u16::MAX)
+            .map(|_| extranonces.acquire().unwrap())
+            .collect();
+
+        // 每个值只分配一次，全部占用后拒绝分配
+        let values: HashSet<_> = leases.iter().map(|lease| lease.value).collect();
+        assert_eq!(values.len(), 1 << 16);
+        assert!(extranonces.acquire().is_none());
+
+        // 连接断开后回收的值可以再次分配
+        let released = leases.swap_remove(42).value;
+        assert_eq!(extranonces.acquire().unwrap().value, released);
+    }
+
+    #[test]
+    fn test_stratum_prevhash() {
+        let hash = H256(core::array::from_fn(|i| i as u8));
+        assert!(stratum_prevhash(&hash).starts_with("0302010007060504"));
+    }
+
+    #[tokio::test]
+    async fn test_read_line() {
+        let mut buf = Vec::new();
+
+        let mut reader: &[u8] = b"{\"id\":1}\r\nlast";
+        assert_eq!(
+            read_line(&mut reader, &mut buf).await.unwrap().as_deref(),
+            Some("{\"id\":1}")
+        );
+        assert_eq!(
+            read_line(&mut reader, &mut buf).await.unwrap().as_deref(),
+            Some("last")
+        );
+        assert_eq!(read_line(&mut reader, &mut buf).await.unwrap(), None);
+
+        // 刚好达到上限的行可以读取，再多一个字节就报错
+        let mut line = vec![b'a'; MAX_LINE_LENGTH];
+        line.push(b'\n');
+        assert_eq!(
+            read_line(&mut &line[..], &mut buf)
+                .await
+                .unwrap()
+                .unwrap()
+                .len(),
+            MAX_LINE_LENGTH
+        );
+        line.insert(0, b'a');
+        let mut reader = &line[..];
+        let err = read_line(&mut reader, &mut buf).await.unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+    }
+
+    #[tokio::test]
+    async fn test_oversized_line_disconnects() {
+        let source = TestSource::new(Difficulty::one());
+        let (addr, _) = start(source, Difficulty::one(), false).await;
+        let mut client = TestClient::connect(addr).await;
+
+        // 不带换行符的超长请求不会被无限缓存，服务端直接断开连接
+        client
+            .writer
+            .write_all(&vec![b' '; MAX_LINE_LENGTH + 1])
+            .await
+            .unwrap();
+        assert!(matches!(client.lines.next_line().await, Ok(None) | Err(_)));
+    }
+
+    #[tokio::test]
+    async fn test_subscribe_authorize_submit() {
+        let source = TestSource::new(Difficulty::one());
+        let (addr, stats) = start(source.clone(), Difficulty::one(), false).await;
+        let mut client = TestClient::connect(addr).await;
+
+        // 订阅返回 extranonce1 和 extranonce2 长度
+        let response = client
+            .call("mining.subscribe", json!(["test-miner/1.0"]))
+            .await;
+        assert_eq!(response["result"][1].as_str().unwrap().len(), 4);
+        assert_eq!(response["result"][2], EXTRANONCE2_SIZE);
+
+        // 随后推送难度和工作
+        let difficulty = client.next().await;
+        assert_eq!(difficulty["method"], "mining.set_difficulty");
+        assert_eq!(difficulty["params"][0], 1);
+        let notify = client.next().await;
+        assert_eq!(notify["method"], "mining.notify");
+        assert_eq!(notify["params"][0], "1");
+        assert_eq!(notify["params"][1], "11".repeat(32));
+
+        // 登录前提交被拒绝
+        let response = client
+            .call("mining.submit", json!(["rig", "1", "0000", "00000001"]))
+            .await;
+        assert_eq!(response["error"][0], ERR_UNAUTHORIZED);
+
+        let response = client.call("mining.authorize", json!(["rig", "x"])).await;
+        assert_eq!(response["result"], true);
+
+        // 难度为 1 时任何份额都满足区块难度
+        let response = client
+            .call("mining.submit", json!(["rig", "1", "0000", "00000001"]))
+            .await;
+        assert_eq!(response["result"], true);
+        assert_eq!(source.submitted.lock().unwrap().len(), 1);
+
+        // 重复份额与未知工作
+        let response = client
+            .call("mining.submit", json!(["rig", "1", "0000", "00000001"]))
+            .await;
+        assert_eq!(response["error"][0], ERR_DUPLICATE_SHARE);
+        let response = client
+            .call("mining.submit", json!(["rig", "2", "0000", "00000002"]))
+            .await;
+        assert_eq!(response["error"][0], ERR_JOB_NOT_FOUND);
+
+        let share = stats.lock().unwrap()["rig"].clone();
+        assert_eq!(
+            share,
+            ShareStats {
+                accepted: 1,
+                rejected: 2,
+                blocks: 1
+            }
+        );
+    }
+
+    #[tokio::test]
+    async fn test_low_difficulty_share_rejected() {
+        let source = TestSource::new(Difficulty::MAX);
+        let (addr, stats) = start(source.clone(), Difficulty::MAX, false).await;
+        let mut client = TestClient::connect(addr).await;
+
+        client.call("mining.subscribe", json!([])).await;
+        client.next().await;
+        client.next().await;
+        client.call("mining.authorize", json!(["rig"])).await;
+
+        let response = client
+            .call("mining.submit", json!(["rig", "1", "0000", "00000001"]))
+            .await;
+        assert_eq!(response["error"][0], ERR_LOW_DIFFICULTY);
+
+        // 重复提交无效份额仍报告难度不足，而不是重复份额
+        let response = client
+            .call("mining.submit", json!(["rig", "1", "0000", "00000001"]))
+            .await;
+        assert_eq!(response["error"][0], ERR_LOW_DIFFICULTY);
+        assert!(source.submitted.lock().unwrap().is_empty());
+        assert_eq!(stats.lock().unwrap()["rig"].rejected, 2);
+    }
+
+    #[tokio::test]
+    async fn test_header_mode_submit() {
+        let source = TestSource::new(Difficulty::one());
+        let (addr, _) = start(source.clone(), Difficulty::one(), true).await;
+        let mut client = TestClient::connect(addr).await;
+
+        let response = client.call("mining.subscribe", json!([])).await;
+        let extranonce1 = response["result"][1].as_str().unwrap();
+        assert_eq!(extranonce1.len(), HEADER_EXTRANONCE1_SIZE * 2);
+        assert_eq!(response["result"][2], HEADER_EXTRANONCE2_SIZE);
+        let extranonce1 = u32::from_str_radix(extranonce1, 16).unwrap();
+
+        // 难度按比特币难度下发
+        let difficulty = client.next().await;
+        assert!(difficulty["params"][0].as_f64().unwrap() < 1e-9);
+
+        // 比特币格式的 notify
+        let notify = client.next().await;
+        let params = notify["params"].as_array().unwrap().clone();
+        assert_eq!(params.len(), 9);
+        assert_eq!(params[5], "20000000");
+        assert_eq!(params[6], format!("{:08x}", source.work.bits));
+        let ntime = u32::from_str_radix(params[7].as_str().unwrap(), 16).unwrap();
+
+        client.call("mining.authorize", json!(["asic"])).await;
+
+        // 按矿机的方式搜索满足紧凑目标的 nonce
+        let work = &source.work;
+        let extranonce = (u64::from(extranonce1) << 32) | 5;
+        let target = bitnice_pow::compact_to_target(work.bits).unwrap();
+        let nonce = (0u32..)
+            .find(|nonce| {
+                let seal = Seal::header(ntime, *nonce, extranonce);
+                let hash = bitnice_pow::compute_header_hash(&work.pre_hash, &seal, work.bits);
+                U256::from_little_endian(hash.as_bytes()) <= target
+            })
+            .unwrap();
+
+        let submit = json!([
+            "asic",
+            "1",
+            "00000005",
+            format!("{:08x}", ntime),
+            format!("{:08x}", nonce)
+        ]);
+        let response = client.call("mining.submit", submit).await;
+        assert_eq!(response["result"], true);
+        let submitted = source.submitted.lock().unwrap().clone();
+        assert_eq!(submitted[0], Seal::header(ntime, nonce, extranonce));
+
+        // ntime 必须等于工作下发的模板时间戳
+        assert_eq!(ntime, source.work.ntime);
+        for ntime in [ntime - 1, ntime + 1] {
+            let submit = json!([
+                "asic",
+                "1",
+                "00000005",
+                format!("{:08x}", ntime),
+                "00000000"
+            ]);
+            let response = client.call("mining.submit", submit).await;
+            assert_eq!(response["error"][0], ERR_OTHER);
+        }
+    }
+}
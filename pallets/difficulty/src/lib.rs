@@ -0,0 +1,191 @@
+#![cfg_attr(not(feature = "std"), no_std)]
+//
+//! BitNice 难度调整 Pallet
+//!
+//! 通过 `pallet_timestamp` 的 `OnTimestampSet` 钩子记录出块时间，
+//! 每经过 `AdjustmentWindow` 个区块按比特币方式重新计算目标：
+//!
+//! ```text
+//! 新目标 = 旧目标 * 实际耗时 / 期望耗时
+//! ```
+//!
+//! 其中实际耗时被限制在 `[期望耗时 / MaxAdjustmentFactor, 期望耗时 * MaxAdjustmentFactor]`
+//! 区间内，避免难度剧烈波动。目标以比特币 nBits 风格的紧凑格式存储，
+//! 节点通过 `DifficultyApi` 读取换算后的 `U256` 难度。
+
+pub use pallet::*;
+
+use sp_core::U256;
+
+#[cfg(test)]
+mod mock;
+#[cfg(test)]
+mod tests;
+
+/// 未在创世配置中指定时使用的初始难度（1,000,000）
+pub const DEFAULT_INITIAL_DIFFICULTY: U256 = U256([1_000_000, 0, 0, 0]);
+
+#[frame_support::pallet]
+pub mod pallet {
+    use bitnice_pow::{
+        compact_to_difficulty, compact_to_target, difficulty_to_compact, difficulty_to_target,
+        target_to_compact,
+    };
+    use frame_support::{pallet_prelude::*, traits::OnTimestampSet};
+    use frame_system::pallet_prelude::*;
+    use sp_core::{U256, U512};
+    use sp_runtime::SaturatedConversion;
+
+    #[pallet::pallet]
+    pub struct Pallet<T>(_);
+
+    #[pallet::config]
+    pub trait Config: frame_system::Config {
+        /// 运行时事件类型
+        type RuntimeEvent: From<Event<Self>> + IsType<<Self as frame_system::Config>::RuntimeEvent>;
+
+        /// 目标出块时间（毫秒）
+        #[pallet::constant]
+        type TargetBlockTime: Get<u64>;
+
+        /// 每隔多少个区块调整一次难度
+        #[pallet::constant]
+        type AdjustmentWindow: Get<u32>;
+
+        /// 单次调整的最大倍数（比特币为 4）
+        #[pallet::constant]
+        type MaxAdjustmentFactor: Get<u32>;
+
+        /// 难度下限
+        #[pallet::constant]
+        type MinDifficulty: Get<U256>;
+    }
+
+    #[pallet::type_value]
+    pub fn DefaultBits() -> u32 {
+        difficulty_to_compact(crate::DEFAULT_INITIAL_DIFFICULTY)
+    }
+
+    /// 当前目标的紧凑编码，下一个区块需要满足该目标
+    #[pallet::storage]
+    pub type CurrentBits<T> = StorageValue<_, u32, ValueQuery, DefaultBits>;
+
+    /// 当前调整窗口起始区块的时间戳（毫秒）
+    #[pallet::storage]
+    pub type WindowStartTime<T> = StorageValue<_, u64, ValueQuery>;
+
+    /// 当前调整窗口的起始区块号
+    #[pallet::storage]
+    pub type WindowStartBlock<T: Config> = StorageValue<_, BlockNumberFor<T>, ValueQuery>;
+
+    #[pallet::event]
+    #[pallet::generate_deposit(pub(super) fn deposit_event)]
+    pub enum Event<T: Config> {
+        /// 难度已调整
+        DifficultyAdjusted {
+            /// 调整前难度
+            previous: U256,
+            /// 调整后难度
+            current: U256,
+            /// 调整后目标的紧凑编码
+            bits: u32,
+            /// 窗口内实际耗时（毫秒）
+            timespan: u64,
+        },
+    }
+
+    #[pallet::genesis_config]
+    pub struct GenesisConfig<T: Config> {
+        /// 创世难度
+        pub initial_difficulty: U256,
+        #[serde(skip)]
+        pub _config: core::marker::PhantomData<T>,
+    }
+
+    impl<T: Config> Default for GenesisConfig<T> {
+        fn default() -> Self {
+            Self {
+                initial_difficulty: crate::DEFAULT_INITIAL_DIFFICULTY,
+                _config: Default::default(),
+            }
+        }
+    }
+
+    #[pallet::genesis_build]
+    impl<T: Config> BuildGenesisConfig for GenesisConfig<T> {
+        fn build(&self) {
+            let difficulty = self.initial_difficulty.max(T::MinDifficulty::get());
+            CurrentBits::<T>::put(difficulty_to_compact(difficulty));
+        }
+    }
+
+    impl<T: Config> Pallet<T> {
+        /// 获取当前难度，紧凑编码无效时回退到难度下限
+        pub fn difficulty() -> U256 {
+            compact_to_difficulty(CurrentBits::<T>::get()).unwrap_or_else(T::MinDifficulty::get)
+        }
+
+        /// 获取当前目标的紧凑编码
+        pub fn bits() -> u32 {
+            CurrentBits::<T>::get()
+        }
+
+        /// 根据窗口耗时计算新目标的紧凑编码
+        ///
+        /// `expected` 与 `actual` 单位均为毫秒
+        pub fn retarget(bits: u32, expected: u64, actual: u64) -> u32 {
+            let factor = u64::from(T::MaxAdjustmentFactor::get().max(1));
+            let min_timespan = (expected / factor).max(1);
+            let max_timespan = expected.saturating_mul(factor).max(min_timespan);
+            let actual = actual.clamp(min_timespan, max_timespan);
+
+            // 难度下限对应的最大目标
+            let max_target = difficulty_to_target(T::MinDifficulty::get());
+            let target = compact_to_target(bits).unwrap_or(max_target);
+
+            // 出块越快目标越小：新目标 = 旧目标 * 实际耗时 / 期望耗时
+            let adjusted = target.full_mul(U256::from(actual)) / U512::from(expected.max(1));
+            let adjusted = U256::try_from(adjusted).unwrap_or(U256::MAX);
+
+            target_to_compact(adjusted.clamp(U256::one(), max_target))
+        }
+    }
+
+    impl<T: Config> OnTimestampSet<u64> for Pallet<T> {
+        fn on_timestamp_set(now: u64) {
+            let block = frame_system::Pallet::<T>::block_number();
+            let start_time = WindowStartTime::<T>::get();
+
+            // 第一个带时间戳的区块，开启第一个窗口
+            if start_time == 0 {
+                WindowStartTime::<T>::put(now);
+                WindowStartBlock::<T>::put(block);
+                return;
+            }
+
+            let window = T::AdjustmentWindow::get().max(1);
+            let elapsed_blocks: u32 = block
+                .saturating_sub(WindowStartBlock::<T>::get())
+                .saturated_into();
+            if elapsed_blocks < window {
+                return;
+            }
+
+            let expected = T::TargetBlockTime::get().saturating_mul(u64::from(elapsed_blocks));
+            let timespan = now.saturating_sub(start_time);
+            let previous = Self::difficulty();
+            let bits = Self::retarget(CurrentBits::<T>::get(), expected, timespan);
+
+            CurrentBits::<T>::put(bits);
+            WindowStartTime::<T>::put(now);
+            WindowStartBlock::<T>::put(block);
+
+            Self::deposit_event(Event::DifficultyAdjusted {
+                previous,
+                current: Self::difficulty(),
+                bits,
+                timespan,
+            });
+        }
+    }
+}
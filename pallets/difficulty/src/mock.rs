@@ -0,0 +1,52 @@
+//! 难度调整 Pallet 测试用运行时
+
+use crate as pallet_difficulty;
+use frame_support::{derive_impl, parameter_types};
+use sp_core::U256;
+use sp_runtime::BuildStorage;
+
+type Block = frame_system::mocking::MockBlock<Test>;
+
+frame_support::construct_runtime!(
+    pub enum Test {
+        System: frame_system,
+        Difficulty: pallet_difficulty,
+    }
+);
+
+#[derive_impl(frame_system::config_preludes::TestDefaultConfig)]
+impl frame_system::Config for Test {
+    type Block = Block;
+}
+
+parameter_types! {
+    pub const TargetBlockTime: u64 = 6000;
+    pub const AdjustmentWindow: u32 = 10;
+    pub const MaxAdjustmentFactor: u32 = 4;
+    pub MinDifficulty: U256 = U256::from(1000u64);
+}
+
+impl pallet_difficulty::Config for Test {
+    type RuntimeEvent = RuntimeEvent;
+    type TargetBlockTime = TargetBlockTime;
+    type AdjustmentWindow = AdjustmentWindow;
+    type MaxAdjustmentFactor = MaxAdjustmentFactor;
+    type MinDifficulty = MinDifficulty;
+}
+
+/// 构建测试环境，创世难度为 1_000_000
+pub fn new_test_ext() -> sp_io::TestExternalities {
+    let mut storage = frame_system::GenesisConfig::<Test>::default()
+        .build_storage()
+        .unwrap();
+    pallet_difficulty::GenesisConfig::<Test> {
+        initial_difficulty: U256::from(1_000_000u64),
+        ..Default::default()
+    }
+    .assimilate_storage(&mut storage)
+    .unwrap();
+
+    let mut ext = sp_io::TestExternalities::new(storage);
+    ext.execute_with(|| System::set_block_number(1));
+    ext
+}
@@ -0,0 +1,133 @@
+//! 难度调整 Pallet 单元测试
+
+use crate::{mock::*, CurrentBits, Event, WindowStartTime};
+use bitnice_pow::{compact_to_difficulty, difficulty_to_compact};
+use frame_support::traits::OnTimestampSet;
+use sp_core::U256;
+use sp_runtime::BuildStorage;
+
+/// 难度经紧凑编码往返后的值，紧凑格式只保留目标最高 3 字节的精度
+fn normalized(difficulty: u128) -> U256 {
+    compact_to_difficulty(difficulty_to_compact(U256::from(difficulty))).unwrap()
+}
+
+/// 断言难度与期望值的相对误差不超过万分之一
+fn assert_approx(actual: U256, expected: u128) {
+    let expected = U256::from(expected);
+    let diff = if actual > expected {
+        actual - expected
+    } else {
+        expected - actual
+    };
+    assert!(
+        diff * U256::from(10_000u64) <= expected,
+        "难度 {} 与期望 {} 相差过大",
+        actual,
+        expected
+    );
+}
+
+/// 以固定出块间隔模拟 `blocks` 个区块
+///
+/// 首次调用时先在 1 号区块写入时间戳以开启第一个窗口
+fn produce_blocks(blocks: u32, interval: u64) {
+    let mut now = WindowStartTime::<Test>::get();
+    if now == 0 {
+        now = 1_000;
+        Difficulty::on_timestamp_set(now);
+    }
+
+    for _ in 0..blocks {
+        System::set_block_number(System::block_number() + 1);
+        now += interval;
+        Difficulty::on_timestamp_set(now);
+    }
+}
+
+#[test]
+fn genesis_difficulty_is_stored() {
+    new_test_ext().execute_with(|| {
+        assert_eq!(
+            Difficulty::bits(),
+            difficulty_to_compact(U256::from(1_000_000u64))
+        );
+        assert_eq!(Difficulty::difficulty(), normalized(1_000_000));
+    });
+}
+
+#[test]
+fn difficulty_unchanged_on_target() {
+    new_test_ext().execute_with(|| {
+        let bits = Difficulty::bits();
+        produce_blocks(10, 6000);
+        assert_eq!(Difficulty::bits(), bits);
+    });
+}
+
+#[test]
+fn fast_blocks_increase_difficulty() {
+    new_test_ext().execute_with(|| {
+        produce_blocks(10, 3000);
+        assert_approx(Difficulty::difficulty(), 2_000_000);
+        System::assert_last_event(
+            Event::DifficultyAdjusted {
+                previous: normalized(1_000_000),
+                current: Difficulty::difficulty(),
+                bits: Difficulty::bits(),
+                timespan: 30_000,
+            }
+            .into(),
+        );
+    });
+}
+
+#[test]
+fn slow_blocks_decrease_difficulty() {
+    new_test_ext().execute_with(|| {
+        produce_blocks(10, 12_000);
+        assert_approx(Difficulty::difficulty(), 500_000);
+    });
+}
+
+#[test]
+fn adjustment_is_clamped() {
+    new_test_ext().execute_with(|| {
+        // 极快出块最多提高 4 倍
+        produce_blocks(10, 1);
+        assert_approx(Difficulty::difficulty(), 4_000_000);
+
+        // 极慢出块最多降低到 1/4
+        produce_blocks(10, 600_000);
+        assert_approx(Difficulty::difficulty(), 1_000_000);
+    });
+}
+
+#[test]
+fn difficulty_never_below_minimum() {
+    new_test_ext().execute_with(|| {
+        CurrentBits::<Test>::put(difficulty_to_compact(U256::from(1_500u64)));
+        produce_blocks(10, 600_000);
+        assert_eq!(Difficulty::difficulty(), normalized(1_000));
+    });
+}
+
+#[test]
+fn genesis_accepts_difficulty_above_u128() {
+    let mut storage = frame_system::GenesisConfig::<Test>::default()
+        .build_storage()
+        .unwrap();
+    let difficulty = U256::from(u128::MAX) * U256::from(1_000u64);
+    crate::GenesisConfig::<Test> {
+        initial_difficulty: difficulty,
+        ..Default::default()
+    }
+    .assimilate_storage(&mut storage)
+    .unwrap();
+
+    sp_io::TestExternalities::new(storage).execute_with(|| {
+        assert_eq!(
+            Difficulty::difficulty(),
+            compact_to_difficulty(difficulty_to_compact(difficulty)).unwrap()
+        );
+    });
+}
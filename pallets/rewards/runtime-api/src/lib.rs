@@ -0,0 +1,17 @@
+#![cfg_attr(not(feature = "std"), no_std)]
+//
+//! BitNice 区块奖励 Pallet 运行时 API
+
+use codec::Codec;
+
+sp_api::decl_runtime_apis! {
+    /// 区块奖励查询接口
+    pub trait RewardsApi<AccountId, Balance>
+    where
+        AccountId: Codec,
+        Balance: Codec,
+    {
+        /// 账户尚未达到成熟深度、仍被冻结的区块奖励
+        fn immature_balance(who: AccountId) -> Balance;
+    }
+}
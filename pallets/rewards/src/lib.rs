@@ -0,0 +1,460 @@
+#![cfg_attr(not(feature = "std"), no_std)]
+//
+//! BitNice 区块奖励 Pallet
+//!
+//! 在 `on_finalize` 中向区块作者（由 PoW 预运行时摘要识别）发放区块补贴：
+//! - 补贴按 `HalvingInterval` 个区块减半，与比特币相同
+//! - 累计发放的补贴（`TotalIssued`）不会超过 `MaxSupply`，创世预挖与销毁的手续费不影响发放计划
+//! - `TotalIssued` 记录累计发放的奖励，`NextHalving` 记录下一次减半的区块高度
+//!
+//! 同时提供 [`DealWithFees`] 交易手续费处理器：与 EIP-1559 相同，基础手续费被销毁，
+//! 小费按 `AuthorFeeShare` 分给区块作者，其余部分进入 `FeeTreasury` 账户（未配置国库时全部归作者）
+//!
+//! 预运行时摘要可以携带 [`Coinbase`] 分配列表：区块补贴与作者的手续费在 `on_finalize`
+//! 合并后按比例支付给各收款账户，剩余部分归作者。矿池无需单独的支付交易即可向参与者分账。
+//! 作者的小费在收取时暂时销毁，记入 `CollectedFees`，在 `on_finalize` 重新发行后分配
+//!
+//! 与比特币的 coinbase 成熟期相同，发放的奖励以 `FreezeReason::CoinbaseMaturity` 冻结
+//! `CoinbaseMaturity` 个区块，防止链重组使已花费的奖励失效。冻结在到期区块的 `on_initialize` 自动解除，
+//! `ImmatureBalance` 记录各账户尚未成熟的奖励
+
+pub use pallet::*;
+
+use codec::{Decode, DecodeAll, Encode, EncodeLike, Input, Output};
+use core::marker::PhantomData;
+use frame_support::{
+    traits::{
+        fungible::{Balanced, Credit},
+        ConstU32, Get, Imbalance, OnUnbalanced,
+    },
+    BoundedVec,
+};
+use sp_runtime::{PerThing, Permill, RuntimeDebug};
+
+#[cfg(test)]
+mod mock;
+#[cfg(test)]
+mod tests;
+
+#[frame_support::pallet]
+pub mod pallet {
+    use super::Coinbase;
+    use frame_support::{
+        pallet_prelude::*,
+        traits::{
+            fungible::{Balanced, Inspect, Mutate, MutateFreeze},
+            FindAuthor, Imbalance,
+        },
+    };
+    use frame_system::pallet_prelude::*;
+    use sp_runtime::{
+        traits::{Saturating, Zero},
+        Percent, SaturatedConversion,
+    };
+
+    /// 奖励使用的余额类型
+    pub type BalanceOf<T> =
+        <<T as Config>::Currency as Inspect<<T as frame_system::Config>::AccountId>>::Balance;
+
+    #[pallet::pallet]
+    pub struct Pallet<T>(_);
+
+    #[pallet::config]
+    pub trait Config: frame_system::Config {
+        /// 运行时事件类型
+        type RuntimeEvent: From<Event<Self>> + IsType<<Self as frame_system::Config>::RuntimeEvent>;
+
+        /// 冻结原因的聚合类型
+        type RuntimeFreezeReason: From<FreezeReason>;
+
+        /// 用于铸造奖励、分配手续费和冻结未成熟奖励的货币
+        type Currency: Mutate<Self::AccountId>
+            + Balanced<Self::AccountId>
+            + MutateFreeze<Self::AccountId, Id = Self::RuntimeFreezeReason>;
+
+        /// 从预运行时摘要中识别区块作者与奖励分配
+        type FindAuthor: FindAuthor<Coinbase<Self::AccountId>>;
+
+        /// 初始区块补贴
+        #[pallet::constant]
+        type InitialReward: Get<BalanceOf<Self>>;
+
+        /// 每隔多少个区块补贴减半
+        #[pallet::constant]
+        type HalvingInterval: Get<BlockNumberFor<Self>>;
+
+        /// 区块补贴的累计发放上限
+        #[pallet::constant]
+        type MaxSupply: Get<BalanceOf<Self>>;
+
+        /// 奖励发放后冻结的区块数，为 0 时不冻结
+        #[pallet::constant]
+        type CoinbaseMaturity: Get<BlockNumberFor<Self>>;
+
+        /// 接收部分手续费的国库账户，为 `None` 时手续费全部归区块作者
+        type FeeTreasury: Get<Option<Self::AccountId>>;
+
+        /// 配置国库账户时，区块作者获得的手续费比例
+        #[pallet::constant]
+        type AuthorFeeShare: Get<Percent>;
+    }
+
+    #[pallet::type_value]
+    pub fn DefaultNextHalving<T: Config>() -> BlockNumberFor<T> {
+        T::HalvingInterval::get()
+    }
+
+    /// 累计发放的区块奖励
+    #[pallet::storage]
+    pub type TotalIssued<T: Config> = StorageValue<_, BalanceOf<T>, ValueQuery>;
+
+    /// 下一次补贴减半的区块高度
+    #[pallet::storage]
+    pub type NextHalving<T: Config> =
+        StorageValue<_, BlockNumberFor<T>, ValueQuery, DefaultNextHalving<T>>;
+
+    /// 当前区块归作者的手续费，在 `on_finalize` 与区块补贴一起分配
+    #[pallet::storage]
+    pub type CollectedFees<T: Config> = StorageValue<_, BalanceOf<T>, ValueQuery>;
+
+    /// 各账户尚未成熟的奖励，即 `CoinbaseMaturity` 冻结的数额
+    #[pallet::storage]
+    pub type ImmatureBalance<T: Config> =
+        StorageMap<_, Blake2_128Concat, T::AccountId, BalanceOf<T>, ValueQuery>;
+
+    /// 按成熟高度记录的奖励，到期时从 `ImmatureBalance` 中扣除并解除冻结
+    #[pallet::storage]
+    pub type MaturingRewards<T: Config> = StorageDoubleMap<
+        _,
+        Twox64Concat,
+        BlockNumberFor<T>,
+        Blake2_128Concat,
+        T::AccountId,
+        BalanceOf<T>,
+        ValueQuery,
+    >;
+
+    /// 本 Pallet 的冻结原因
+    #[pallet::composite_enum]
+    pub enum FreezeReason {
+        /// 区块奖励尚未达到成熟深度
+        #[codec(index = 0)]
+        CoinbaseMaturity,
+    }
+
+    #[pallet::event]
+    #[pallet::generate_deposit(pub(super) fn deposit_event)]
+    pub enum Event<T: Config> {
+        /// 已向区块作者发放奖励
+        Rewarded {
+            /// 区块作者
+            author: T::AccountId,
+            /// 奖励数额
+            amount: BalanceOf<T>,
+        },
+        /// 已按奖励分配向收款账户支付
+        PayeeRewarded {
+            /// 收款账户
+            payee: T::AccountId,
+            /// 支付数额
+            amount: BalanceOf<T>,
+        },
+        /// 区块补贴已减半
+        Halved {
+            /// 减半后的补贴
+            reward: BalanceOf<T>,
+            /// 下一次减半的区块高度
+            next_halving: BlockNumberFor<T>,
+        },
+    }
+
+    #[pallet::hooks]
+    impl<T: Config> Hooks<BlockNumberFor<T>> for Pallet<T> {
+        fn on_initialize(n: BlockNumberFor<T>) -> Weight {
+            // 每个区块最多向作者和 MAX_PAYEES 个收款账户发放奖励
+            let accounts = u64::from(super::MAX_PAYEES) + 1;
+            let matured = Self::mature(n);
+
+            // 解冻到期奖励：每个账户读写到期记录、未成熟余额与冻结；
+            // 并为 on_finalize 预留权重：读取摘要、发行量、减半高度与手续费，写入各账户余额、冻结与统计
+            T::DbWeight::get().reads_writes(
+                3 * matured + 5 + 3 * accounts,
+                3 * matured + 5 + 4 * accounts,
+            )
+        }
+
+        fn on_finalize(n: BlockNumberFor<T>) {
+            let fees = CollectedFees::<T>::take();
+            if let Some(coinbase) = Self::coinbase() {
+                Self::reward(&coinbase, n, fees);
+            }
+
+            let interval = T::HalvingInterval::get();
+            let next_halving = NextHalving::<T>::get();
+            if !interval.is_zero() && n >= next_halving {
+                let next_halving = next_halving.saturating_add(interval);
+                NextHalving::<T>::put(next_halving);
+                Self::deposit_event(Event::Halved {
+                    reward: Self::block_subsidy(n),
+                    next_halving,
+                });
+            }
+        }
+    }
+
+    impl<T: Config> Pallet<T> {
+        /// 账户尚未成熟（被冻结）的奖励
+        pub fn immature_balance(who: &T::AccountId) -> BalanceOf<T> {
+            ImmatureBalance::<T>::get(who)
+        }
+
+        /// 冻结刚发放给 `who` 的奖励，直到区块 `n` 之后 `CoinbaseMaturity` 个区块
+        fn freeze(who: &T::AccountId, n: BlockNumberFor<T>, amount: BalanceOf<T>) {
+            let maturity = T::CoinbaseMaturity::get();
+            if maturity.is_zero() || amount.is_zero() {
+                return;
+            }
+
+            // 冻结成功后才记录，保证 ImmatureBalance 与实际冻结一致
+            let immature = ImmatureBalance::<T>::get(who).saturating_add(amount);
+            let reason = FreezeReason::CoinbaseMaturity.into();
+            if let Err(e) = T::Currency::set_freeze(&reason, who, immature) {
+                frame_support::defensive!(e, "MaxFreezes 至少容纳 CoinbaseMaturity 冻结原因");
+                return;
+            }
+
+            ImmatureBalance::<T>::insert(who, immature);
+            MaturingRewards::<T>::mutate(n.saturating_add(maturity), who, |maturing| {
+                *maturing = maturing.saturating_add(amount)
+            });
+        }
+
+        /// 解除在区块 `n` 成熟的奖励的冻结，返回处理的账户数
+        fn mature(n: BlockNumberFor<T>) -> u64 {
+            let reason = FreezeReason::CoinbaseMaturity.into();
+            let mut accounts = 0;
+            for (who, amount) in MaturingRewards::<T>::drain_prefix(n) {
+                let immature = ImmatureBalance::<T>::get(&who).saturating_sub(amount);
+                if immature.is_zero() {
+                    ImmatureBalance::<T>::remove(&who);
+                    let _ = T::Currency::thaw(&reason, &who);
+                } else {
+                    ImmatureBalance::<T>::insert(&who, immature);
+                    let _ = T::Currency::set_freeze(&reason, &who, immature);
+                }
+                accounts += 1;
+            }
+
+            accounts
+        }
+
+        /// 从当前区块的预运行时摘要中识别作者与奖励分配
+        ///
+        /// 节点在导入时拒绝无效的摘要（见 [`Coinbase::from_digest`]），
+        /// 运行时不再中止区块，分配无效时视为没有作者
+        pub fn coinbase() -> Option<Coinbase<T::AccountId>> {
+            let digest = frame_system::Pallet::<T>::digest();
+            let pre_runtime_digests = digest.logs.iter().filter_map(|d| d.as_pre_runtime());
+
+            T::FindAuthor::find_author(pre_runtime_digests).filter(Coinbase::is_valid)
+        }
+
+        /// 从当前区块的预运行时摘要中识别作者
+        pub fn author() -> Option<T::AccountId> {
+            Self::coinbase().map(|coinbase| coinbase.author)
+        }
+
+        /// 计算指定高度的区块补贴（未考虑总量上限）
+        pub fn block_subsidy(n: BlockNumberFor<T>) -> BalanceOf<T> {
+            let interval = T::HalvingInterval::get();
+            if interval.is_zero() {
+                return T::InitialReward::get();
+            }
+
+            let halvings: u32 = (n / interval).saturated_into();
+            // 右移超过 63 位后补贴恒为 0
+            if halvings >= 64 {
+                return Zero::zero();
+            }
+
+            T::InitialReward::get() >> halvings
+        }
+
+        /// 发放区块 `n` 的补贴与作者的手续费 `fees`，按奖励分配支付，返回实际发放的补贴
+        fn reward(
+            coinbase: &Coinbase<T::AccountId>,
+            n: BlockNumberFor<T>,
+            fees: BalanceOf<T>,
+        ) -> BalanceOf<T> {
+            // 上限只约束累计补贴，手续费是已有代币的转移，不占用额度
+            let remaining = T::MaxSupply::get().saturating_sub(TotalIssued::<T>::get());
+            let subsidy = Self::block_subsidy(n).min(remaining);
+            let total = subsidy.saturating_add(fees);
+            if total.is_zero() {
+                return total;
+            }
+
+            let mut credit = T::Currency::issue(total);
+            for (payee, share) in &coinbase.payees {
+                let (part, rest) = credit.split(share.mul_floor(total));
+                credit = rest;
+                let amount = part.peek();
+                if amount.is_zero() {
+                    continue;
+                }
+
+                // 低于存在性押金等原因无法入账的部分归作者
+                match T::Currency::resolve(payee, part) {
+                    Ok(()) => {
+                        Self::freeze(payee, n, amount);
+                        Self::deposit_event(Event::PayeeRewarded {
+                            payee: payee.clone(),
+                            amount,
+                        });
+                    }
+                    Err(part) => credit = credit.merge(part),
+                }
+            }
+
+            // 作者也无法入账时剩余部分随 Credit 释放而销毁，不计入已发放的补贴
+            let amount = credit.peek();
+            let unpaid = match T::Currency::resolve(&coinbase.author, credit) {
+                Ok(()) => {
+                    Self::freeze(&coinbase.author, n, amount);
+                    Zero::zero()
+                }
+                Err(credit) => credit.peek(),
+            };
+            let issued = subsidy.saturating_sub(unpaid);
+
+            TotalIssued::<T>::mutate(|total| *total = total.saturating_add(issued));
+            if unpaid.is_zero() && !amount.is_zero() {
+                Self::deposit_event(Event::Rewarded {
+                    author: coinbase.author.clone(),
+                    amount,
+                });
+            }
+
+            issued
+        }
+    }
+}
+
+/// PoW 预运行时摘要中奖励分配的最大收款账户数量
+pub const MAX_PAYEES: u32 = 16;
+
+/// PoW 预运行时摘要：区块作者与可选的奖励分配
+///
+/// 编码为 `author ‖ payees`，没有分配时只编码作者，与只写入 `AccountId` 的摘要兼容。
+/// 每个收款账户获得补贴与作者手续费之和的 `Permill` 比例，剩余部分归作者
+#[derive(Clone, PartialEq, Eq, RuntimeDebug)]
+pub struct Coinbase<AccountId> {
+    /// 区块作者，签名封印并获得剩余奖励
+    pub author: AccountId,
+    /// 收款账户与分配比例
+    pub payees: BoundedVec<(AccountId, Permill), ConstU32<MAX_PAYEES>>,
+}
+
+impl<AccountId> Coinbase<AccountId> {
+    /// 没有奖励分配的摘要
+    pub fn new(author: AccountId) -> Self {
+        Self {
+            author,
+            payees: BoundedVec::new(),
+        }
+    }
+
+    /// 解码 PoW 预运行时摘要，无法完整解码（含多余字节或收款账户超过上限）或分配无效时返回 `None`
+    ///
+    /// 节点的导入校验与运行时的作者识别都通过它判定摘要，两侧结果一致
+    pub fn from_digest(mut data: &[u8]) -> Option<Self>
+    where
+        AccountId: Decode,
+    {
+        Self::decode_all(&mut data).ok().filter(Self::is_valid)
+    }
+
+    /// 分配比例均不为 0 且合计不超过 100%
+    pub fn is_valid(&self) -> bool {
+        let total: u64 = self
+            .payees
+            .iter()
+            .map(|(_, share)| u64::from(share.deconstruct()))
+            .sum();
+
+        self.payees.iter().all(|(_, share)| !share.is_zero())
+            && total <= u64::from(Permill::ACCURACY)
+    }
+}
+
+impl<AccountId: Encode> Encode for Coinbase<AccountId> {
+    fn encode_to<O: Output + ?Sized>(&self, dest: &mut O) {
+        self.author.encode_to(dest);
+        if !self.payees.is_empty() {
+            self.payees.encode_to(dest);
+        }
+    }
+}
+
+impl<AccountId: Encode> EncodeLike for Coinbase<AccountId> {}
+
+impl<AccountId: Decode> Decode for Coinbase<AccountId> {
+    fn decode<I: Input>(input: &mut I) -> Result<Self, codec::Error> {
+        let author = AccountId::decode(input)?;
+        // 只有作者的摘要没有分配列表
+        let payees = match input.remaining_len()? {
+            Some(0) => BoundedVec::new(),
+            _ => BoundedVec::decode(input)?,
+        };
+
+        Ok(Self { author, payees })
+    }
+}
+
+/// 手续费使用的负余额类型
+pub type CreditOf<T> = Credit<<T as frame_system::Config>::AccountId, <T as Config>::Currency>;
+
+/// 交易手续费处理器
+///
+/// 用于 `pallet_transaction_payment::FungibleAdapter`，它依次给出基础手续费与小费：
+/// - 基础手续费（权重费 + 长度费）被销毁，减少总发行量
+/// - 小费按 `AuthorFeeShare` 分给当前区块作者，其余进入 `FeeTreasury`
+/// - 作者的部分记入 `CollectedFees`，在 `on_finalize` 与区块补贴一起按奖励分配支付
+/// - 找不到作者时全部进入国库；既无作者也无国库时小费同样被销毁
+pub struct DealWithFees<T>(PhantomData<T>);
+
+impl<T: Config> OnUnbalanced<CreditOf<T>> for DealWithFees<T> {
+    fn on_unbalanceds(mut fees_then_tips: impl Iterator<Item = CreditOf<T>>) {
+        // 基础手续费随 Credit 释放而销毁
+        let _ = fees_then_tips.next();
+        for tip in fees_then_tips {
+            Self::on_unbalanced(tip);
+        }
+    }
+
+    fn on_nonzero_unbalanced(fees: CreditOf<T>) {
+        let treasury = T::FeeTreasury::get();
+
+        let (to_author, to_treasury) = match treasury {
+            Some(_) => {
+                let author_amount = T::AuthorFeeShare::get().mul_floor(fees.peek());
+                fees.split(author_amount)
+            }
+            None => (fees, CreditOf::<T>::zero()),
+        };
+
+        // 作者的部分暂时销毁，在 on_finalize 重新发行；作者缺失时转交国库
+        let to_treasury = match Pallet::<T>::author() {
+            Some(_) => {
+                CollectedFees::<T>::mutate(|fees| *fees = fees.saturating_add(to_author.peek()));
+                to_treasury
+            }
+            None => to_treasury.merge(to_author),
+        };
+
+        // 国库无法入账或未配置国库时，剩余部分随 Credit 释放而销毁
+        if let Some(treasury) = treasury {
+            let _ = T::Currency::resolve(&treasury, to_treasury);
+        }
+    }
+}
@@ -0,0 +1,85 @@
+//! 区块奖励 Pallet 测试用运行时
+
+use crate::{self as pallet_rewards, Coinbase};
+use frame_support::{derive_impl, parameter_types, traits::FindAuthor};
+use sp_runtime::{BuildStorage, ConsensusEngineId, Percent, Permill};
+
+type Block = frame_system::mocking::MockBlock<Test>;
+
+frame_support::construct_runtime!(
+    pub enum Test {
+        System: frame_system,
+        Balances: pallet_balances,
+        Rewards: pallet_rewards,
+    }
+);
+
+#[derive_impl(frame_system::config_preludes::TestDefaultConfig)]
+impl frame_system::Config for Test {
+    type Block = Block;
+    type AccountData = pallet_balances::AccountData<u64>;
+}
+
+#[derive_impl(pallet_balances::config_preludes::TestDefaultConfig)]
+impl pallet_balances::Config for Test {
+    type AccountStore = System;
+    type FreezeIdentifier = RuntimeFreezeReason;
+    type MaxFreezes = frame_support::traits::VariantCountOf<RuntimeFreezeReason>;
+    type RuntimeFreezeReason = RuntimeFreezeReason;
+}
+
+parameter_types! {
+    /// 当前区块作者，测试中可通过 `Author::set` 修改
+    pub static Author: Option<u64> = Some(1);
+    /// 当前区块的奖励分配
+    pub static Payees: Vec<(u64, Permill)> = vec![];
+    pub static MaxSupply: u64 = 1_000_000;
+    pub static CoinbaseMaturity: u64 = 0;
+    pub static FeeTreasury: Option<u64> = None;
+    pub const AuthorFeeShare: Percent = Percent::from_percent(80);
+}
+
+/// 直接返回 `Author` 与 `Payees` 的作者识别器
+pub struct MockAuthor;
+
+impl FindAuthor<Coinbase<u64>> for MockAuthor {
+    fn find_author<'a, I>(_digests: I) -> Option<Coinbase<u64>>
+    where
+        I: 'a + IntoIterator<Item = (ConsensusEngineId, &'a [u8])>,
+    {
+        Author::get().map(|author| Coinbase {
+            author,
+            payees: Payees::get().try_into().expect("测试分配不超过上限"),
+        })
+    }
+}
+
+impl pallet_rewards::Config for Test {
+    type RuntimeEvent = RuntimeEvent;
+    type RuntimeFreezeReason = RuntimeFreezeReason;
+    type Currency = Balances;
+    type FindAuthor = MockAuthor;
+    type InitialReward = frame_support::traits::ConstU64<50>;
+    type HalvingInterval = frame_support::traits::ConstU64<10>;
+    type MaxSupply = MaxSupply;
+    type CoinbaseMaturity = CoinbaseMaturity;
+    type FeeTreasury = FeeTreasury;
+    type AuthorFeeShare = AuthorFeeShare;
+}
+
+/// 构建测试环境，账户 2 创世持有 100
+pub fn new_test_ext() -> sp_io::TestExternalities {
+    let mut storage = frame_system::GenesisConfig::<Test>::default()
+        .build_storage()
+        .unwrap();
+    pallet_balances::GenesisConfig::<Test> {
+        balances: vec![(2, 100)],
+        ..Default::default()
+    }
+    .assimilate_storage(&mut storage)
+    .unwrap();
+
+    let mut ext = sp_io::TestExternalities::new(storage);
+    ext.execute_with(|| System::set_block_number(1));
+    ext
+}
@@ -0,0 +1,282 @@
+//! 区块奖励 Pallet 单元测试
+
+use crate::{
+    mock::*, Coinbase, CollectedFees, DealWithFees, Event, ImmatureBalance, NextHalving,
+    TotalIssued,
+};
+use codec::{Decode, Encode};
+use frame_support::traits::{
+    fungible::{Balanced, Inspect},
+    tokens::{Fortitude, Precision, Preservation},
+    Hooks, Imbalance, OnUnbalanced,
+};
+use sp_runtime::Permill;
+
+/// 从当前区块开始依次执行 `blocks` 个区块的钩子
+fn finalize_blocks(blocks: u64) {
+    for _ in 0..blocks {
+        let n = System::block_number();
+        Rewards::on_initialize(n);
+        Rewards::on_finalize(n);
+        System::set_block_number(n + 1);
+    }
+}
+
+#[test]
+fn author_receives_block_subsidy() {
+    new_test_ext().execute_with(|| {
+        finalize_blocks(1);
+        assert_eq!(Balances::balance(&1), 50);
+        assert_eq!(TotalIssued::<Test>::get(), 50);
+        System::assert_has_event(
+            Event::Rewarded {
+                author: 1,
+                amount: 50,
+            }
+            .into(),
+        );
+    });
+}
+
+#[test]
+fn no_author_no_reward() {
+    new_test_ext().execute_with(|| {
+        Author::set(None);
+        finalize_blocks(1);
+        assert_eq!(TotalIssued::<Test>::get(), 0);
+    });
+}
+
+#[test]
+fn subsidy_halves_on_schedule() {
+    new_test_ext().execute_with(|| {
+        assert_eq!(Rewards::block_subsidy(1), 50);
+        assert_eq!(Rewards::block_subsidy(9), 50);
+        assert_eq!(Rewards::block_subsidy(10), 25);
+        assert_eq!(Rewards::block_subsidy(20), 12);
+        assert_eq!(Rewards::block_subsidy(10 * 64), 0);
+
+        assert_eq!(NextHalving::<Test>::get(), 10);
+        // 区块 1..=9 各得 50，区块 10 得 25
+        finalize_blocks(10);
+        assert_eq!(Balances::balance(&1), 9 * 50 + 25);
+        assert_eq!(NextHalving::<Test>::get(), 20);
+    });
+}
+
+#[test]
+fn subsidy_never_exceeds_max_supply() {
+    new_test_ext().execute_with(|| {
+        // 上限只计累计补贴，创世预挖的 100 不占用额度
+        MaxSupply::set(120);
+        finalize_blocks(3);
+        assert_eq!(Balances::balance(&1), 120);
+        assert_eq!(Balances::total_issuance(), 220);
+        assert_eq!(TotalIssued::<Test>::get(), 120);
+
+        // 销毁基础手续费不会腾出额度再次发放
+        let paid = Balances::withdraw(
+            &2,
+            30,
+            Precision::Exact,
+            Preservation::Expendable,
+            Fortitude::Polite,
+        )
+        .unwrap();
+        DealWithFees::<Test>::on_unbalanceds([paid].into_iter());
+        finalize_blocks(1);
+        assert_eq!(Balances::balance(&1), 120);
+        assert_eq!(Balances::total_issuance(), 190);
+        assert_eq!(TotalIssued::<Test>::get(), 120);
+    });
+}
+
+#[test]
+fn fees_go_to_author_without_treasury() {
+    new_test_ext().execute_with(|| {
+        let issuance = Balances::total_issuance();
+        DealWithFees::<Test>::on_unbalanced(Balances::issue(30));
+        // 作者的手续费在区块结束时与补贴一起发放
+        assert_eq!(CollectedFees::<Test>::get(), 30);
+        assert_eq!(Balances::total_issuance(), issuance);
+
+        finalize_blocks(1);
+        assert_eq!(Balances::balance(&1), 80);
+        assert_eq!(TotalIssued::<Test>::get(), 50);
+        assert_eq!(CollectedFees::<Test>::get(), 0);
+    });
+}
+
+#[test]
+fn base_fee_is_burned_and_tip_goes_to_author() {
+    new_test_ext().execute_with(|| {
+        let paid = Balances::withdraw(
+            &2,
+            50,
+            Precision::Exact,
+            Preservation::Expendable,
+            Fortitude::Polite,
+        )
+        .unwrap();
+        // 基础手续费 40 被销毁，小费 10 归作者
+        let (base_fee, tip) = paid.split(40);
+        DealWithFees::<Test>::on_unbalanceds([base_fee, tip].into_iter());
+        // 小费在区块结束时重新发行
+        assert_eq!(Balances::total_issuance(), 50);
+        assert_eq!(CollectedFees::<Test>::get(), 10);
+
+        finalize_blocks(1);
+        assert_eq!(Balances::balance(&1), 60);
+        assert_eq!(Balances::total_issuance(), 110);
+    });
+}
+
+#[test]
+fn fees_split_between_author_and_treasury() {
+    new_test_ext().execute_with(|| {
+        FeeTreasury::set(Some(9));
+        DealWithFees::<Test>::on_unbalanced(Balances::issue(100));
+        assert_eq!(Balances::balance(&9), 20);
+
+        finalize_blocks(1);
+        assert_eq!(Balances::balance(&1), 50 + 80);
+    });
+}
+
+#[test]
+fn coinbase_splits_subsidy_and_fees() {
+    new_test_ext().execute_with(|| {
+        Payees::set(vec![
+            (3, Permill::from_percent(50)),
+            (4, Permill::from_percent(25)),
+        ]);
+        DealWithFees::<Test>::on_unbalanced(Balances::issue(50));
+        finalize_blocks(1);
+
+        // 补贴 50 与手续费 50 合计 100，剩余 25% 归作者
+        assert_eq!(Balances::balance(&3), 50);
+        assert_eq!(Balances::balance(&4), 25);
+        assert_eq!(Balances::balance(&1), 25);
+        assert_eq!(TotalIssued::<Test>::get(), 50);
+        System::assert_has_event(
+            Event::PayeeRewarded {
+                payee: 3,
+                amount: 50,
+            }
+            .into(),
+        );
+        System::assert_has_event(
+            Event::Rewarded {
+                author: 1,
+                amount: 25,
+            }
+            .into(),
+        );
+    });
+}
+
+#[test]
+fn coinbase_over_full_share_is_ignored() {
+    new_test_ext().execute_with(|| {
+        Payees::set(vec![
+            (3, Permill::from_percent(60)),
+            (4, Permill::from_percent(50)),
+        ]);
+        // 节点在导入时拒绝此类区块，运行时视为没有作者
+        finalize_blocks(1);
+        assert_eq!(Rewards::coinbase(), None);
+        assert_eq!(Balances::balance(&1), 0);
+        assert_eq!(TotalIssued::<Test>::get(), 0);
+    });
+}
+
+#[test]
+fn rewards_are_frozen_until_mature() {
+    new_test_ext().execute_with(|| {
+        CoinbaseMaturity::set(3);
+        let spendable =
+            || Balances::reducible_balance(&1, Preservation::Expendable, Fortitude::Polite);
+
+        // 区块 1、2 的奖励分别在区块 4、5 成熟
+        finalize_blocks(2);
+        assert_eq!(Balances::balance(&1), 100);
+        assert_eq!(Rewards::immature_balance(&1), 100);
+        assert_eq!(spendable(), 0);
+
+        // 区块 4 开始时解冻区块 1 的奖励，随后发放的区块 4 奖励继续冻结
+        finalize_blocks(2);
+        assert_eq!(Balances::balance(&1), 200);
+        assert_eq!(Rewards::immature_balance(&1), 150);
+        assert_eq!(spendable(), 50);
+
+        // 没有新奖励后，区块 5..=7 依次解冻区块 2..=4 的奖励
+        Author::set(None);
+        finalize_blocks(3);
+        assert_eq!(Rewards::immature_balance(&1), 0);
+        assert!(!ImmatureBalance::<Test>::contains_key(1));
+        assert_eq!(spendable(), 200);
+    });
+}
+
+#[test]
+fn coinbase_digest_encoding() {
+    // 只有作者时与直接编码账户相同
+    let author_only = Coinbase::new(1u64);
+    assert_eq!(author_only.encode(), 1u64.encode());
+    assert_eq!(
+        Coinbase::<u64>::decode(&mut &1u64.encode()[..]).unwrap(),
+        author_only
+    );
+
+    let coinbase = Coinbase {
+        author: 1u64,
+        payees: vec![
+            (3, Permill::from_percent(40)),
+            (4, Permill::from_percent(60)),
+        ]
+        .try_into()
+        .unwrap(),
+    };
+    assert!(coinbase.is_valid());
+    assert_eq!(
+        Coinbase::decode(&mut &coinbase.encode()[..]).unwrap(),
+        coinbase
+    );
+
+    assert_eq!(
+        Coinbase::from_digest(&coinbase.encode()),
+        Some(coinbase.clone())
+    );
+
+    // 超过上限的分配列表与多余字节都无法解码
+    let too_many = (1u64, vec![(3u64, Permill::from_percent(1)); 17]).encode();
+    assert!(Coinbase::<u64>::decode(&mut &too_many[..]).is_err());
+    assert_eq!(Coinbase::<u64>::from_digest(&too_many), None);
+    let mut trailing = coinbase.encode();
+    trailing.push(0);
+    assert_eq!(Coinbase::<u64>::from_digest(&trailing), None);
+
+    // 比例为 0 的收款账户无效
+    let zero = Coinbase {
+        author: 1u64,
+        payees: vec![(3, Permill::zero())].try_into().unwrap(),
+    };
+    assert!(!zero.is_valid());
+    assert_eq!(Coinbase::<u64>::from_digest(&zero.encode()), None);
+}
+
+#[test]
+fn fees_without_author_go_to_treasury_or_burn() {
+    new_test_ext().execute_with(|| {
+        Author::set(None);
+        let issuance = Balances::total_issuance();
+
+        // 既无作者也无国库时手续费被销毁
+        DealWithFees::<Test>::on_unbalanced(Balances::issue(10));
+        assert_eq!(Balances::total_issuance(), issuance);
+
+        FeeTreasury::set(Some(9));
+        DealWithFees::<Test>::on_unbalanced(Balances::issue(10));
+        assert_eq!(Balances::balance(&9), 10);
+    });
+}
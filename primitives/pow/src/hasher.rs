@@ -0,0 +1,188 @@
+//! 原生封印的批量哈希
+//!
+//! 原生封印的哈希输入为 `pre_hash ‖ nonce ‖ extranonce ‖ version`，共 49 字节，
+//! 填充后恰好是一个 64 字节分组。同一工作下只有 nonce 变化：
+//!
+//! ```text
+//! 消息字 W0..W7   = pre_hash         固定，前 8 轮压缩可以预先计算（中间状态）
+//! 消息字 W8..W9   = nonce（小端）    每次变化
+//! 消息字 W10..W15 = extranonce、版本与填充，固定
+//! ```
+//!
+//! [`SealHasher`] 构造时写好固定部分，每个 nonce 只改写两个消息字。
+//! 可移植实现从中间状态继续计算剩余 56 轮；CPU 支持 SHA 扩展时改用 `sha2` 的压缩函数，
+//! 由硬件完成整个分组。两种实现的结果与 [`compute_hash`](crate::compute_hash) 一致。
+
+use sha2::digest::generic_array::{typenum::U64, GenericArray};
+use sp_core::{H256, U256};
+
+use crate::SEAL_VERSION;
+
+/// SHA-256 初始哈希值
+const IV: [u32; 8] = [
+    0x6a09e667, 0xbb67ae85, 0x3c6ef372, 0xa54ff53a, 0x510e527f, 0x9b05688c, 0x1f83d9ab, 0x5be0cd19,
+];
+
+/// SHA-256 轮常量
+const K: [u32; 64] = [
+    0x428a2f98, 0x71374491, 0xb5c0fbcf, 0xe9b5dba5, 0x3956c25b, 0x59f111f1, 0x923f82a4, 0xab1c5ed5,
+    0xd807aa98, 0x12835b01, 0x243185be, 0x550c7dc3, 0x72be5d74, 0x80deb1fe, 0x9bdc06a7, 0xc19bf174,
+    0xe49b69c1, 0xefbe4786, 0x0fc19dc6, 0x240ca1cc, 0x2de92c6f, 0x4a7484aa, 0x5cb0a9dc, 0x76f988da,
+    0x983e5152, 0xa831c66d, 0xb00327c8, 0xbf597fc7, 0xc6e00bf3, 0xd5a79147, 0x06ca6351, 0x14292967,
+    0x27b70a85, 0x2e1b2138, 0x4d2c6dfc, 0x53380d13, 0x650a7354, 0x766a0abb, 0x81c2c92e, 0x92722c85,
+    0xa2bfe8a1, 0xa81a664b, 0xc24b8b70, 0xc76c51a3, 0xd192e819, 0xd6990624, 0xf40e3585, 0x106aa070,
+    0x19a4c116, 0x1e376c08, 0x2748774c, 0x34b0bcb5, 0x391c0cb3, 0x4ed8aa4a, 0x5b9cca4f, 0x682e6ff3,
+    0x748f82ee, 0x78a5636f, 0x84c87814, 0x8cc70208, 0x90befffa, 0xa4506ceb, 0xbef9a3f7, 0xc67178f2,
+];
+
+/// 只依赖 pre_hash 的轮数
+const PREFIX_ROUNDS: usize = 8;
+
+/// 原生封印哈希输入的位数：32 字节 pre_hash + 17 字节 SCALE 封印
+const INPUT_BITS: u64 = 49 * 8;
+
+/// 压缩函数的实现方式
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Backend {
+    /// 可移植实现：预计算前 8 轮，每个 nonce 只计算剩余 56 轮
+    Midstate,
+    /// `sha2` 的压缩函数，CPU 支持 SHA 扩展时由硬件计算
+    Compress,
+}
+
+/// 固定 pre_hash 与 extranonce 的原生封印哈希器
+#[derive(Clone)]
+pub struct SealHasher {
+    /// 填充后的消息分组，nonce 所在的 8 字节在哈希时改写
+    block: GenericArray<u8, U64>,
+    /// 分组的 16 个大端消息字
+    words: [u32; 16],
+    /// 前 8 轮压缩后的工作变量
+    midstate: [u32; 8],
+}
+
+impl SealHasher {
+    /// 为当前版本的封印构造哈希器
+    pub fn new(pre_hash: &H256, extranonce: u64) -> Self {
+        let mut block = GenericArray::<u8, U64>::default();
+        block[..32].copy_from_slice(pre_hash.as_bytes());
+        block[40..48].copy_from_slice(&extranonce.to_le_bytes());
+        block[48] = SEAL_VERSION;
+        block[49] = 0x80;
+        block[56..].copy_from_slice(&INPUT_BITS.to_be_bytes());
+
+        let words: [u32; 16] = core::array::from_fn(|i| {
+            u32::from_be_bytes([
+                block[4 * i],
+                block[4 * i + 1],
+                block[4 * i + 2],
+                block[4 * i + 3],
+            ])
+        });
+
+        let mut midstate = IV;
+        for (w, k) in words.iter().zip(K).take(PREFIX_ROUNDS) {
+            round(&mut midstate, *w, k);
+        }
+
+        Self {
+            block,
+            words,
+            midstate,
+        }
+    }
+
+    /// 计算 `nonce` 对应的摘要（大端消息字）
+    pub fn digest(&mut self, backend: Backend, nonce: u64) -> [u32; 8] {
+        match backend {
+            Backend::Midstate => self.digest_midstate(nonce),
+            Backend::Compress => self.digest_compress(nonce),
+        }
+    }
+
+    /// 计算 `nonce` 对应的封印哈希
+    pub fn hash(&mut self, backend: Backend, nonce: u64) -> H256 {
+        digest_to_hash(&self.digest(backend, nonce))
+    }
+
+    /// 在 `[start, start + count)` 中搜索摘要不大于 `target` 的 nonce
+    pub fn search(
+        &mut self,
+        backend: Backend,
+        start: u64,
+        count: u64,
+        target: &[u32; 8],
+    ) -> Option<u64> {
+        (start..start.saturating_add(count)).find(|nonce| self.digest(backend, *nonce) <= *target)
+    }
+
+    fn digest_midstate(&self, nonce: u64) -> [u32; 8] {
+        let mut w = [0u32; 64];
+        w[..16].copy_from_slice(&self.words);
+        // SCALE 以小端编码 nonce，消息字按大端读取
+        w[8] = (nonce as u32).swap_bytes();
+        w[9] = ((nonce >> 32) as u32).swap_bytes();
+        for i in 16..64 {
+            w[i] = small_sigma1(w[i - 2])
+                .wrapping_add(w[i - 7])
+                .wrapping_add(small_sigma0(w[i - 15]))
+                .wrapping_add(w[i - 16]);
+        }
+
+        let mut state = self.midstate;
+        for (w, k) in w.iter().zip(K).skip(PREFIX_ROUNDS) {
+            round(&mut state, *w, k);
+        }
+
+        core::array::from_fn(|i| state[i].wrapping_add(IV[i]))
+    }
+
+    fn digest_compress(&mut self, nonce: u64) -> [u32; 8] {
+        self.block[32..40].copy_from_slice(&nonce.to_le_bytes());
+
+        let mut state = IV;
+        sha2::compress256(&mut state, core::slice::from_ref(&self.block));
+        state
+    }
+}
+
+/// 目标换算为大端消息字，与摘要按字典序比较等价于按大端整数比较
+pub fn target_words(target: U256) -> [u32; 8] {
+    core::array::from_fn(|i| (target >> (224 - 32 * i)).low_u32())
+}
+
+/// 摘要换算为哈希
+pub fn digest_to_hash(digest: &[u32; 8]) -> H256 {
+    let mut hash = H256::zero();
+    for (bytes, word) in hash.0.chunks_exact_mut(4).zip(digest) {
+        bytes.copy_from_slice(&word.to_be_bytes());
+    }
+    hash
+}
+
+#[inline(always)]
+fn round(state: &mut [u32; 8], w: u32, k: u32) {
+    let [a, b, c, d, e, f, g, h] = *state;
+    let s1 = e.rotate_right(6) ^ e.rotate_right(11) ^ e.rotate_right(25);
+    let ch = (e & f) ^ (!e & g);
+    let t1 = h
+        .wrapping_add(s1)
+        .wrapping_add(ch)
+        .wrapping_add(k)
+        .wrapping_add(w);
+    let s0 = a.rotate_right(2) ^ a.rotate_right(13) ^ a.rotate_right(22);
+    let maj = (a & b) ^ (a & c) ^ (b & c);
+    let t2 = s0.wrapping_add(maj);
+
+    *state = [t1.wrapping_add(t2), a, b, c, d.wrapping_add(t1), e, f, g];
+}
+
+#[inline(always)]
+fn small_sigma0(x: u32) -> u32 {
+    x.rotate_right(7) ^ x.rotate_right(18) ^ (x >> 3)
+}
+
+#[inline(always)]
+fn small_sigma1(x: u32) -> u32 {
+    x.rotate_right(17) ^ x.rotate_right(19) ^ (x >> 10)
+}
@@ -0,0 +1,268 @@
+#![cfg_attr(not(feature = "std"), no_std)]
+//
+//! BitNice PoW 原语
+//!
+//! 封印格式、封印哈希与难度/目标换算只在这里定义，运行时、节点导入校验、
+//! 挖矿线程、Stratum 服务和独立矿工都依赖本 crate：
+//!
+//! ```text
+//! 封印哈希 = SHA-256(pre_hash ‖ SCALE(Seal))
+//! 目标     = (2^256 - 1) / 难度
+//! 有效封印：封印哈希（大端整数）<= 目标
+//! ```
+//!
+//! `hash <= (2^256 - 1) / difficulty` 与 `hash * difficulty` 不溢出 256 位等价。
+//!
+//! 版本 2 的封印面向 SHA-256d 矿机，把工作映射为比特币 80 字节区块头：
+//!
+//! ```text
+//! 区块头   = version ‖ pre_hash ‖ merkle_root ‖ ntime ‖ bits ‖ nonce32（整数均为小端）
+//! merkle_root = SHA-256d(extranonce 大端 8 字节)
+//! 封印哈希 = SHA-256d(区块头)
+//! 有效封印：封印哈希（小端整数）<= 紧凑目标 bits
+//! ```
+//!
+//! 其中 `ntime` 与 `nonce32` 分别是封印 `nonce` 的高 32 位和低 32 位，
+//! `bits` 由区块难度换算，矿机只需把 `pre_hash` 当作上一区块哈希即可按比特币方式挖矿。
+//!
+//! 区块有作者（PoW 预运行时摘要）时，封印由作者的 `pow_` sr25519 密钥签名，
+//! 以 [`SignedSeal`] 写入区块头，签名消息为 `SCALE(pre_hash, Seal)`，
+//! 转发封印的矿池或中继无法把解据为己有。
+//!
+//! 链上以比特币 nBits 风格的 32 位紧凑格式保存目标：最高字节为目标的字节长度，
+//! 低 23 位为尾数，第 24 位为符号位（置位且尾数非零时无效）。
+//!
+//! 奖励地址使用 BitNice 的 SS58 前缀 [`SS58_PREFIX`]，节点与独立矿工通过 `std` 下的
+//! [`parse_coinbase`] 解析命令行中的奖励地址。
+
+extern crate alloc;
+
+use alloc::vec::Vec;
+use codec::{Decode, Encode};
+use scale_info::TypeInfo;
+use sha2::{Digest, Sha256};
+use sp_core::{crypto::KeyTypeId, sr25519, H256, U256};
+
+mod hasher;
+#[cfg(test)]
+mod tests;
+
+pub use hasher::{digest_to_hash, target_words, Backend, SealHasher};
+
+/// BitNice 地址的 SS58 前缀
+pub const SS58_PREFIX: u16 = 42;
+
+/// 当前封印版本
+pub const SEAL_VERSION: u8 = 1;
+
+/// 80 字节区块头模式的封印版本
+pub const HEADER_SEAL_VERSION: u8 = 2;
+
+/// 区块头模式写入区块头的版本号（比特币 BIP9 版本位格式）
+pub const HEADER_VERSION: u32 = 0x2000_0000;
+
+/// 比特币风格区块头的长度
+pub const HEADER_SIZE: usize = 80;
+
+/// 封印签名密钥的类型，使用 `key insert --key-type pow_ --scheme sr25519` 写入密钥库
+pub const KEY_TYPE: KeyTypeId = KeyTypeId(*b"pow_");
+
+/// 写入区块头的封印
+#[derive(Clone, Copy, PartialEq, Eq, Encode, Decode, TypeInfo, Debug, Default)]
+pub struct Seal {
+    /// 挖矿找到的随机数
+    pub nonce: u64,
+    /// 额外随机数，由矿工或矿池分配，扩展搜索空间
+    pub extranonce: u64,
+    /// 封印版本，决定哈希方式
+    pub version: u8,
+}
+
+impl Seal {
+    /// 创建当前版本的封印
+    pub fn new(nonce: u64, extranonce: u64) -> Self {
+        Self {
+            nonce,
+            extranonce,
+            version: SEAL_VERSION,
+        }
+    }
+
+    /// 创建区块头模式的封印，`ntime` 为区块头时间戳（秒）
+    pub fn header(ntime: u32, nonce: u32, extranonce: u64) -> Self {
+        Self {
+            nonce: (u64::from(ntime) << 32) | u64::from(nonce),
+            extranonce,
+            version: HEADER_SEAL_VERSION,
+        }
+    }
+
+    /// 区块头模式下的时间戳（`nonce` 高 32 位）
+    pub fn ntime(&self) -> u32 {
+        (self.nonce >> 32) as u32
+    }
+
+    /// 区块头模式下的 32 位 nonce（`nonce` 低 32 位）
+    pub fn header_nonce(&self) -> u32 {
+        self.nonce as u32
+    }
+}
+
+/// 计算封印哈希：SHA-256(pre_hash ‖ SCALE(seal))
+pub fn compute_hash(pre_hash: &H256, seal: &Seal) -> H256 {
+    let mut hasher = Sha256::new();
+    hasher.update(pre_hash.as_bytes());
+    hasher.update(seal.encode());
+
+    H256::from_slice(&hasher.finalize())
+}
+
+/// 双重 SHA-256
+pub fn sha256d(data: &[u8]) -> [u8; 32] {
+    Sha256::digest(Sha256::digest(data)).into()
+}
+
+/// 区块头模式的 merkle 根：只有一笔“coinbase”，内容为大端编码的 extranonce
+pub fn header_merkle_root(extranonce: u64) -> [u8; 32] {
+    sha256d(&extranonce.to_be_bytes())
+}
+
+/// 构造区块头模式下的 80 字节区块头
+pub fn header_bytes(pre_hash: &H256, seal: &Seal, bits: u32) -> [u8; HEADER_SIZE] {
+    let mut header = [0u8; HEADER_SIZE];
+    header[0..4].copy_from_slice(&HEADER_VERSION.to_le_bytes());
+    header[4..36].copy_from_slice(pre_hash.as_bytes());
+    header[36..68].copy_from_slice(&header_merkle_root(seal.extranonce));
+    header[68..72].copy_from_slice(&seal.ntime().to_le_bytes());
+    header[72..76].copy_from_slice(&bits.to_le_bytes());
+    header[76..80].copy_from_slice(&seal.header_nonce().to_le_bytes());
+    header
+}
+
+/// 计算区块头模式的封印哈希：SHA-256d(80 字节区块头)，按哈希输出的原始字节顺序返回
+pub fn compute_header_hash(pre_hash: &H256, seal: &Seal, bits: u32) -> H256 {
+    H256(sha256d(&header_bytes(pre_hash, seal, bits)))
+}
+
+/// 判断区块头哈希（按比特币方式视为小端整数）是否满足难度要求
+pub fn header_hash_meets_difficulty(hash: &H256, difficulty: U256) -> bool {
+    U256::from_little_endian(hash.as_bytes()) <= difficulty_to_target(difficulty)
+}
+
+/// 难度换算为目标：`(2^256 - 1) / difficulty`，难度为 0 时按 1 处理
+pub fn difficulty_to_target(difficulty: U256) -> U256 {
+    U256::MAX / difficulty.max(U256::one())
+}
+
+/// 目标换算为难度：`(2^256 - 1) / target`，目标为 0 时难度为最大值
+pub fn target_to_difficulty(target: U256) -> U256 {
+    if target.is_zero() {
+        return U256::MAX;
+    }
+
+    U256::MAX / target
+}
+
+/// 目标编码为紧凑格式，只保留最高 3 字节的精度
+pub fn target_to_compact(target: U256) -> u32 {
+    let mut size = (target.bits() + 7) / 8;
+    let mut compact = if size <= 3 {
+        (target.low_u64() << (8 * (3 - size))) as u32
+    } else {
+        (target >> (8 * (size - 3))).low_u32()
+    };
+
+    // 尾数最高位是符号位，置位时尾数右移一字节、长度加一
+    if compact & 0x0080_0000 != 0 {
+        compact >>= 8;
+        size += 1;
+    }
+
+    compact | ((size as u32) << 24)
+}
+
+/// 解码紧凑格式的目标，负数或超出 256 位时返回 `None`
+pub fn compact_to_target(compact: u32) -> Option<U256> {
+    let size = compact >> 24;
+    let mantissa = compact & 0x007f_ffff;
+
+    if mantissa != 0 && compact & 0x0080_0000 != 0 {
+        return None;
+    }
+
+    if size <= 3 {
+        return Some(U256::from(mantissa >> (8 * (3 - size))));
+    }
+
+    let overflow = mantissa != 0
+        && (size > 34 || (mantissa > 0xff && size > 33) || (mantissa > 0xffff && size > 32));
+    if overflow {
+        return None;
+    }
+
+    Some(U256::from(mantissa) << (8 * (size - 3) as usize))
+}
+
+/// 难度换算为紧凑目标
+pub fn difficulty_to_compact(difficulty: U256) -> u32 {
+    target_to_compact(difficulty_to_target(difficulty))
+}
+
+/// 紧凑目标换算为难度，编码无效时返回 `None`
+pub fn compact_to_difficulty(compact: u32) -> Option<U256> {
+    compact_to_target(compact).map(target_to_difficulty)
+}
+
+/// 判断哈希是否满足难度要求
+pub fn hash_meets_difficulty(hash: &H256, difficulty: U256) -> bool {
+    U256::from_big_endian(hash.as_bytes()) <= difficulty_to_target(difficulty)
+}
+
+/// 带作者签名的封印，区块有作者时写入区块头
+#[derive(Clone, PartialEq, Eq, Encode, Decode, TypeInfo, Debug)]
+pub struct SignedSeal {
+    /// 封印
+    pub seal: Seal,
+    /// 作者 `pow_` 密钥对 [`signing_payload`] 的签名
+    pub signature: sr25519::Signature,
+}
+
+/// 封印签名的消息：`SCALE(pre_hash, seal)`，签名同时绑定工作与随机数
+pub fn signing_payload(pre_hash: &H256, seal: &Seal) -> Vec<u8> {
+    (pre_hash, seal).encode()
+}
+
+/// 校验封印：版本受支持且封印哈希满足难度
+///
+/// 区块头模式与比特币一致，使用由难度换算的紧凑目标 `bits` 判断
+pub fn verify(pre_hash: &H256, seal: &Seal, difficulty: U256) -> bool {
+    match seal.version {
+        SEAL_VERSION => hash_meets_difficulty(&compute_hash(pre_hash, seal), difficulty),
+        HEADER_SEAL_VERSION => {
+            let bits = difficulty_to_compact(difficulty);
+            let hash = compute_header_hash(pre_hash, seal, bits);
+            compact_to_target(bits)
+                .is_some_and(|target| U256::from_little_endian(hash.as_bytes()) <= target)
+        }
+        _ => false,
+    }
+}
+
+/// 将 SS58 地址解析为奖励账户，地址前缀必须为 [`SS58_PREFIX`]
+#[cfg(feature = "std")]
+pub fn parse_coinbase(address: &str) -> Result<sp_core::crypto::AccountId32, String> {
+    use sp_core::crypto::{AccountId32, Ss58Codec};
+
+    let (account, format) = AccountId32::from_ss58check_with_version(address)
+        .map_err(|err| format!("无效的奖励地址 {}: {:?}", address, err))?;
+
+    let prefix = u16::from(format);
+    if prefix != SS58_PREFIX {
+        return Err(format!(
+            "奖励地址 {} 的 SS58 前缀为 {}，应为 {}",
+            address, prefix, SS58_PREFIX
+        ));
+    }
+
+    Ok(account)
+}
@@ -0,0 +1,263 @@
+//! 封印哈希与难度换算测试，测试向量见 `test-vectors/vectors.json`
+
+use super::*;
+use serde_json::Value;
+
+fn vectors() -> Value {
+    serde_json::from_str(include_str!("../test-vectors/vectors.json")).unwrap()
+}
+
+fn bytes(value: &Value) -> Vec<u8> {
+    hex::decode(value.as_str().unwrap().trim_start_matches("0x")).unwrap()
+}
+
+/// 将整数转换为大端字节的哈希
+fn to_hash(value: U256) -> H256 {
+    H256(core::array::from_fn(|i| value.byte(31 - i)))
+}
+
+fn u256(value: &Value) -> U256 {
+    U256::from_str_radix(value.as_str().unwrap().trim_start_matches("0x"), 16).unwrap()
+}
+
+#[test]
+fn seal_vectors() {
+    for vector in vectors()["seals"].as_array().unwrap() {
+        let pre_hash = H256::from_slice(&bytes(&vector["pre_hash"]));
+        let seal = Seal {
+            nonce: vector["nonce"].as_u64().unwrap(),
+            extranonce: vector["extranonce"].as_u64().unwrap(),
+            version: vector["version"].as_u64().unwrap() as u8,
+        };
+
+        assert_eq!(seal.encode(), bytes(&vector["encoded_seal"]));
+        assert_eq!(
+            Seal::decode(&mut &bytes(&vector["encoded_seal"])[..]).unwrap(),
+            seal
+        );
+        assert_eq!(
+            compute_hash(&pre_hash, &seal).as_bytes(),
+            &bytes(&vector["hash"])[..]
+        );
+    }
+}
+
+#[test]
+fn target_vectors() {
+    for vector in vectors()["targets"].as_array().unwrap() {
+        let difficulty = u256(&vector["difficulty"]);
+        let target = u256(&vector["target"]);
+
+        assert_eq!(difficulty_to_target(difficulty), target);
+        assert_eq!(
+            target_to_difficulty(target),
+            u256(&vector["difficulty_from_target"])
+        );
+    }
+}
+
+#[test]
+fn target_matches_overflow_rule() {
+    // hash <= 目标 与 hash * difficulty 不溢出等价
+    let difficulty = U256::from(1000u64);
+    let target = difficulty_to_target(difficulty);
+
+    let above = target + 1;
+
+    assert!(hash_meets_difficulty(&to_hash(target), difficulty));
+    assert!(!target.overflowing_mul(difficulty).1);
+    assert!(!hash_meets_difficulty(&to_hash(above), difficulty));
+    assert!(above.overflowing_mul(difficulty).1);
+}
+
+#[test]
+fn zero_values() {
+    assert_eq!(difficulty_to_target(U256::zero()), U256::MAX);
+    assert_eq!(target_to_difficulty(U256::zero()), U256::MAX);
+}
+
+#[test]
+fn verify_checks_version_and_difficulty() {
+    let pre_hash = H256::repeat_byte(0x11);
+    let seal = Seal::new(42, 7);
+
+    // 难度为 1 时任何哈希都满足
+    assert!(verify(&pre_hash, &seal, U256::one()));
+    assert!(!verify(&pre_hash, &seal, U256::MAX));
+
+    let unknown_version = Seal { version: 0, ..seal };
+    assert!(!verify(&pre_hash, &unknown_version, U256::one()));
+}
+
+#[test]
+fn compact_vectors() {
+    for vector in vectors()["compact"].as_array().unwrap() {
+        let compact = u32::from_str_radix(
+            vector["compact"].as_str().unwrap().trim_start_matches("0x"),
+            16,
+        )
+        .unwrap();
+
+        if vector["target"].is_null() {
+            assert_eq!(compact_to_target(compact), None);
+            continue;
+        }
+
+        let target = u256(&vector["target"]);
+        assert_eq!(compact_to_target(compact), Some(target));
+        assert_eq!(
+            target_to_compact(target),
+            u256(&vector["normalized"]).low_u32()
+        );
+    }
+}
+
+#[test]
+fn difficulty_compact_vectors() {
+    for vector in vectors()["difficulty_compact"].as_array().unwrap() {
+        let compact = u256(&vector["compact"]).low_u32();
+
+        assert_eq!(difficulty_to_compact(u256(&vector["difficulty"])), compact);
+        assert_eq!(
+            compact_to_difficulty(compact),
+            Some(u256(&vector["difficulty_from_compact"]))
+        );
+    }
+}
+
+#[test]
+fn header_vectors() {
+    for vector in vectors()["headers"].as_array().unwrap() {
+        let pre_hash = H256::from_slice(&bytes(&vector["pre_hash"]));
+        let seal = Seal::header(
+            vector["ntime"].as_u64().unwrap() as u32,
+            vector["nonce"].as_u64().unwrap() as u32,
+            vector["extranonce"].as_u64().unwrap(),
+        );
+        let bits = u256(&vector["bits"]).low_u32();
+
+        assert_eq!(difficulty_to_compact(u256(&vector["difficulty"])), bits);
+        assert_eq!(
+            &header_bytes(&pre_hash, &seal, bits)[..],
+            &bytes(&vector["header"])[..]
+        );
+        assert_eq!(
+            compute_header_hash(&pre_hash, &seal, bits).as_bytes(),
+            &bytes(&vector["hash"])[..]
+        );
+    }
+}
+
+#[test]
+fn sha256d_matches_bitcoin_genesis() {
+    let genesis = &vectors()["bitcoin_genesis"];
+    let mut hash = sha256d(&bytes(&genesis["header"]));
+    // 比特币按小端整数显示区块哈希
+    hash.reverse();
+
+    assert_eq!(&hash[..], &bytes(&genesis["hash"])[..]);
+}
+
+#[test]
+fn verify_header_seal() {
+    let pre_hash = H256::repeat_byte(0x11);
+    let seal = Seal::header(1_700_000_000, 42, 7);
+
+    assert_eq!(seal.ntime(), 1_700_000_000);
+    assert_eq!(seal.header_nonce(), 42);
+    assert_eq!(seal.version, HEADER_SEAL_VERSION);
+
+    // 难度 1 的紧凑目标为 0xffff << 240，约每 256 个 nonce 出现一个解
+    let solved = (0..)
+        .map(|nonce| Seal::header(1_700_000_000, nonce, 7))
+        .find(|seal| verify(&pre_hash, seal, U256::one()))
+        .unwrap();
+    assert!(!verify(&pre_hash, &solved, U256::MAX));
+    assert!(!verify(
+        &pre_hash,
+        &Seal {
+            version: 3,
+            ..solved
+        },
+        U256::one()
+    ));
+
+    // 同一哈希按小端整数比较
+    let hash = compute_header_hash(&pre_hash, &seal, difficulty_to_compact(U256::one()));
+    let mut reversed = hash.0;
+    reversed.reverse();
+    let value = U256::from_big_endian(&reversed);
+    assert_eq!(
+        header_hash_meets_difficulty(&hash, U256::from(2u64)),
+        value <= difficulty_to_target(U256::from(2u64))
+    );
+}
+
+#[test]
+fn seal_hasher_matches_compute_hash() {
+    let pre_hash = H256::repeat_byte(0xab);
+    let mut hasher = SealHasher::new(&pre_hash, 7);
+
+    for nonce in [0, 1, 42, 0x0123_4567_89ab_cdef, u64::MAX] {
+        let expected = compute_hash(&pre_hash, &Seal::new(nonce, 7));
+        assert_eq!(hasher.hash(Backend::Midstate, nonce), expected);
+        assert_eq!(hasher.hash(Backend::Compress, nonce), expected);
+    }
+}
+
+#[test]
+fn seal_hasher_search_finds_first_solution() {
+    let pre_hash = H256::repeat_byte(0x11);
+    let difficulty = U256::from(16u64);
+    let target = target_words(difficulty_to_target(difficulty));
+
+    let expected = (0..)
+        .find(|nonce| {
+            hash_meets_difficulty(&compute_hash(&pre_hash, &Seal::new(*nonce, 0)), difficulty)
+        })
+        .unwrap();
+
+    for backend in [Backend::Midstate, Backend::Compress] {
+        let mut hasher = SealHasher::new(&pre_hash, 0);
+        assert_eq!(
+            hasher.search(backend, 0, expected + 1, &target),
+            Some(expected)
+        );
+        assert_eq!(hasher.search(backend, 0, expected, &target), None);
+    }
+}
+
+#[test]
+fn signing_payload_binds_pre_hash_and_seal() {
+    let pre_hash = H256::repeat_byte(0x11);
+    let seal = Seal::new(42, 7);
+    let payload = signing_payload(&pre_hash, &seal);
+
+    assert_eq!(&payload[..32], pre_hash.as_bytes());
+    assert_eq!(&payload[32..], &seal.encode()[..]);
+
+    let signed = SignedSeal {
+        seal,
+        signature: sr25519::Signature::from_raw([0xab; 64]),
+    };
+    let encoded = signed.encode();
+    // 签名封印 = 封印 ‖ 64 字节签名
+    assert_eq!(encoded.len(), 17 + 64);
+    assert_eq!(SignedSeal::decode(&mut &encoded[..]).unwrap(), signed);
+}
+
+#[test]
+fn parse_coinbase_requires_bitnice_prefix() {
+    // Alice 的通用 SS58 地址（前缀 42）
+    let alice = parse_coinbase("5GrwvaEF5zXb26Fz9rcQpDWS57CtERHpNehXCPcNoHGKutQY").unwrap();
+    assert_eq!(
+        hex::encode(<[u8; 32]>::from(alice)),
+        "d43593c715fdd31c61141abd04a99fd6822c8558854ccde39a5684e7a56da27d"
+    );
+
+    // 格式错误的地址
+    assert!(parse_coinbase("not-an-address").is_err());
+
+    // Alice 的 Polkadot 地址（前缀 0）
+    assert!(parse_coinbase("15oF4uVJwmo4TdGW7VfQxNLavjCXviqxT9S1MgbjMNHr6Sp5").is_err());
+}
@@ -0,0 +1,115 @@
+//! BitNice 创世配置预设
+//!
+//! 提供 `development`、`local_testnet` 和 `staging` 三个预设，供链规范和 `GenesisBuilder` 运行时 API 使用。
+//! 前两者使用私钥公开的开发账户，`staging` 使用运营方账户
+
+use crate::{AccountId, Balance, UNIT};
+use alloc::{vec, vec::Vec};
+use serde_json::Value;
+use sp_core::U256;
+use sp_genesis_builder::{self, PresetId};
+
+/// 预发布测试网预设名称
+pub const STAGING_RUNTIME_PRESET: &str = "staging";
+
+/// 测试账户的初始余额：每个账户 1,000,000 BN
+const ENDOWMENT: Balance = 1_000_000 * UNIT;
+
+/// 开发账户的 sr25519 公钥，由 `//Alice`、`//Alice//stash` 等公开派生路径生成
+const ALICE: &str = "d43593c715fdd31c61141abd04a99fd6822c8558854ccde39a5684e7a56da27d";
+const BOB: &str = "8eaf04151687736326c9fea17e25fc5287613693c912909cb226aa4794f26a48";
+const CHARLIE: &str = "90b5ab205c6974c9ea841be688864633dc9ca8a357843eeacf2314649965fe22";
+const DAVE: &str = "306721211d5404bd9da88e0204360a1a9ab8b87c66c1bc2fcdd37f3c2222cc20";
+const EVE: &str = "e659a7a1628cdd93febc04a4e0646ea20e9f5f0ce097d9a05290d4a9e054df4e";
+const FERDIE: &str = "1cbd2d43530a44705ad088af313e18f80b53ef16b36177cd4b77b846f2a5f07c";
+const ALICE_STASH: &str = "be5ddb1579b72e84524fc29e78609e3caf42e85aa118ebfe0b0ad404b5bdd25f";
+const BOB_STASH: &str = "fe65717dad0447d715f660a0a58411de509b42e6efb8375f562f58a554d5860e";
+const CHARLIE_STASH: &str = "1e07379407fecc4b89eb7dbd287c2c781cfb1907a96947a3eb18e4f8e7198625";
+const DAVE_STASH: &str = "e860f1b1c7227f7c22602f53f15af80747814dffd839719731ee3bba6edc126c";
+const EVE_STASH: &str = "8ac59e11963af19174d0b94d5d78041c233f55d2e19324665bafdfb62925af2d";
+const FERDIE_STASH: &str = "101191192fc877c24d725b337120fa3edc63d227bbc92705db1e2cb65f56981a";
+
+/// 预发布测试网运营方账户公钥（占位值，不对应任何公开派生路径）
+///
+/// 部署前替换为运营方以 `subkey generate` 生成的公钥，私钥不进入仓库
+const STAGING_SUDO: &str = "2370a95dcb7af839711429d645f4eae20d66e47cd6e6a7671f410ed4b376dacd";
+const STAGING_TREASURY: &str = "507f8bf8c6164014ca13d039a89551240b99853f32dba99b63b87ac124d6ceeb";
+
+/// 由十六进制公钥构造账户
+fn account_id(public: &str) -> AccountId {
+    let mut raw = [0u8; 32];
+    hex::decode_to_slice(public, &mut raw).expect("账户公钥为 32 字节十六进制");
+    AccountId::new(raw)
+}
+
+/// 生成创世配置补丁
+fn testnet_genesis(endowed_accounts: Vec<AccountId>, root: AccountId, initial_difficulty: U256) -> Value {
+    serde_json::json!({
+        "balances": {
+            "balances": endowed_accounts
+                .into_iter()
+                .map(|account| (account, ENDOWMENT))
+                .collect::<Vec<_>>(),
+        },
+        "sudo": { "key": Some(root) },
+        "difficulty": { "initialDifficulty": initial_difficulty },
+    })
+}
+
+/// 开发链：Alice 为超级用户，难度较低便于单机 CPU 挖矿
+pub fn development_config_genesis() -> Value {
+    testnet_genesis(
+        [ALICE, BOB, ALICE_STASH, BOB_STASH].into_iter().map(account_id).collect(),
+        account_id(ALICE),
+        U256::from(100_000u64),
+    )
+}
+
+/// 本地测试网：所有开发账户均有初始余额
+pub fn local_config_genesis() -> Value {
+    testnet_genesis(
+        [
+            ALICE, BOB, CHARLIE, DAVE, EVE, FERDIE,
+            ALICE_STASH, BOB_STASH, CHARLIE_STASH, DAVE_STASH, EVE_STASH, FERDIE_STASH,
+        ]
+        .into_iter()
+        .map(account_id)
+        .collect(),
+        account_id(ALICE),
+        U256::from(1_000_000u64),
+    )
+}
+
+/// 预发布测试网：只为运营方账户预留余额，难度面向多矿工网络
+pub fn staging_config_genesis() -> Value {
+    testnet_genesis(
+        [STAGING_SUDO, STAGING_TREASURY].into_iter().map(account_id).collect(),
+        account_id(STAGING_SUDO),
+        U256::from(10_000_000u64),
+    )
+}
+
+/// 按名称获取预设
+pub fn get_preset(id: &PresetId) -> Option<Vec<u8>> {
+    let patch = match id.as_ref() {
+        sp_genesis_builder::DEV_RUNTIME_PRESET => development_config_genesis(),
+        sp_genesis_builder::LOCAL_TESTNET_RUNTIME_PRESET => local_config_genesis(),
+        STAGING_RUNTIME_PRESET => staging_config_genesis(),
+        _ => return None,
+    };
+
+    Some(
+        serde_json::to_string(&patch)
+            .expect("创世配置补丁可以序列化为 JSON")
+            .into_bytes(),
+    )
+}
+
+/// 所有可用的预设名称
+pub fn preset_names() -> Vec<PresetId> {
+    vec![
+        PresetId::from(sp_genesis_builder::DEV_RUNTIME_PRESET),
+        PresetId::from(sp_genesis_builder::LOCAL_TESTNET_RUNTIME_PRESET),
+        PresetId::from(STAGING_RUNTIME_PRESET),
+    ]
+}
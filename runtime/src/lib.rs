@@ -5,15 +5,26 @@
 //! - 共识算法可扩展（后续可集成 PoW）
 //! - 区块时间 6 秒
 //! - 代币名称：BitNice，符号：BN，总量：2,100,000,000
-//! - Pallet: System、Timestamp、Balances、TransactionPayment、Sudo
+//! - Pallet: System、Timestamp、Balances、TransactionPayment、Sudo、Difficulty、Rewards
 
 extern crate alloc;
+
+pub mod genesis_config_presets;
+
 use alloc::vec::Vec;
-use sp_core::OpaqueMetadata;
+use frame_support::dispatch::DispatchClass;
+use frame_support::traits::{FindAuthor, Get};
+use frame_support::weights::{
+    constants::{ExtrinsicBaseWeight, RocksDbWeight, WEIGHT_REF_TIME_PER_SECOND},
+    Weight, WeightToFeeCoefficient, WeightToFeeCoefficients, WeightToFeePolynomial,
+};
+use frame_system::limits::{BlockLength, BlockWeights};
+use pallet_transaction_payment::Multiplier;
+use sp_core::{OpaqueMetadata, U256};
 use sp_runtime::{
     generic,
     traits::{BlakeTwo256, IdentifyAccount, Verify},
-    MultiAddress, MultiSignature,
+    ConsensusEngineId, FixedPointNumber, MultiAddress, MultiSignature, Perbill, Perquintill,
 };
 use sp_version::RuntimeVersion;
 
@@ -32,10 +43,10 @@ pub const VERSION: RuntimeVersion = RuntimeVersion {
     spec_name: alloc::borrow::Cow::Borrowed("bitnice"),
     impl_name: alloc::borrow::Cow::Borrowed("bitnice"),
     authoring_version: 1,
-    spec_version: 100,
+    spec_version: 106,
     impl_version: 1,
-    apis: alloc::borrow::Cow::Borrowed(&[]),
-    transaction_version: 1,
+    apis: RUNTIME_API_VERSIONS,
+    transaction_version: 2,
     system_version: 1,
 };
 
@@ -48,6 +59,36 @@ pub const UNIT: u128 = 1_000_000_000_000;
 pub const TOTAL_SUPPLY: u128 = 2_100_000_000 * UNIT;
 pub const EXISTENTIAL_DEPOSIT: u128 = UNIT / 1000;
 
+pub const MILLI_UNIT: u128 = UNIT / 1000;
+pub const MICRO_UNIT: u128 = UNIT / 1_000_000;
+
+/// 区块执行上限：6 秒出块中留 2 秒执行交易，其余用于传播与导入
+pub const MAXIMUM_BLOCK_WEIGHT: Weight =
+    Weight::from_parts(WEIGHT_REF_TIME_PER_SECOND.saturating_mul(2), u64::MAX);
+/// 区块长度上限（字节），出块节点的打包上限与之一致
+pub const MAX_BLOCK_LENGTH: u32 = 4 * 1024 * 1024;
+/// 普通交易可使用的权重与长度比例，剩余部分留给 Operational 交易
+pub const NORMAL_DISPATCH_RATIO: Perbill = Perbill::from_percent(75);
+
+/// 交易基础权重的费用：一笔普通转账（含长度费）约 0.02 BN
+pub const EXTRINSIC_BASE_FEE: Balance = 10 * MILLI_UNIT;
+/// 每字节交易长度的费用
+pub const TRANSACTION_BYTE_FEE: Balance = 10 * MICRO_UNIT;
+
+/// 基础费率每个区块最多变化 1/8，与 EIP-1559 相同
+pub const BASE_FEE_MAX_CHANGE_DENOMINATOR: u64 = 8;
+
+/// SS58 地址前缀，与节点和独立矿工共用 `bitnice_pow` 中的定义
+pub use bitnice_pow::SS58_PREFIX;
+
+/// 区块奖励：初始 50 BN，约每 4 年（21,000,000 个区块）减半，
+/// 全部发放完毕时恰好为 TOTAL_SUPPLY
+pub const INITIAL_BLOCK_REWARD: u128 = 50 * UNIT;
+pub const HALVING_INTERVAL: u32 = 21_000_000;
+
+/// 区块奖励的成熟深度：与比特币相同，发放后 100 个区块内不可花费
+pub const COINBASE_MATURITY: BlockNumber = 100;
+
 /// 类型定义
 pub type Signature = MultiSignature;
 pub type AccountId = <<Signature as Verify>::Signer as IdentifyAccount>::AccountId;
@@ -58,7 +99,22 @@ pub type BlockNumber = u32;
 pub type Address = MultiAddress<AccountId, ()>;
 pub type Header = generic::Header<BlockNumber, BlakeTwo256>;
 pub type Block = generic::Block<Header, UncheckedExtrinsic>;
-pub type UncheckedExtrinsic = generic::UncheckedExtrinsic<Address, RuntimeCall, Signature, ()>;
+
+/// 签名交易的扩展检查，交易池校验与区块执行时依次运行：
+/// 发送者非零、规范版本、交易版本、创世哈希、有效期、nonce、区块权重、手续费
+pub type TxExtension = (
+    frame_system::CheckNonZeroSender<Runtime>,
+    frame_system::CheckSpecVersion<Runtime>,
+    frame_system::CheckTxVersion<Runtime>,
+    frame_system::CheckGenesis<Runtime>,
+    frame_system::CheckMortality<Runtime>,
+    frame_system::CheckNonce<Runtime>,
+    frame_system::CheckWeight<Runtime>,
+    pallet_transaction_payment::ChargeTransactionPayment<Runtime>,
+);
+pub type UncheckedExtrinsic = generic::UncheckedExtrinsic<Address, RuntimeCall, Signature, TxExtension>;
+/// 钱包签名的负载：调用 + 扩展 + 扩展的隐式数据
+pub type SignedPayload = generic::SignedPayload<RuntimeCall, TxExtension>;
 
 /// Opaque 类型定义
 pub mod opaque {
@@ -74,15 +130,26 @@ frame_support::construct_runtime!(
         Balances: pallet_balances,
         TransactionPayment: pallet_transaction_payment,
         Sudo: pallet_sudo,
+        Difficulty: pallet_difficulty,
+        Rewards: pallet_rewards,
     }
 );
 
+frame_support::parameter_types! {
+    /// 供 CheckSpecVersion/CheckTxVersion 读取
+    pub const Version: RuntimeVersion = VERSION;
+    pub RuntimeBlockWeights: BlockWeights =
+        BlockWeights::with_sensible_defaults(MAXIMUM_BLOCK_WEIGHT, NORMAL_DISPATCH_RATIO);
+    pub RuntimeBlockLength: BlockLength =
+        BlockLength::max_with_normal_ratio(MAX_BLOCK_LENGTH, NORMAL_DISPATCH_RATIO);
+}
+
 /// System Pallet 配置
 impl frame_system::Config for Runtime {
     type BaseCallFilter = frame_support::traits::Everything;
-    type BlockWeights = ();
-    type BlockLength = ();
-    type DbWeight = ();
+    type BlockWeights = RuntimeBlockWeights;
+    type BlockLength = RuntimeBlockLength;
+    type DbWeight = RocksDbWeight;
     type RuntimeOrigin = RuntimeOrigin;
     type RuntimeCall = RuntimeCall;
     type Nonce = Nonce;
@@ -93,13 +160,13 @@ impl frame_system::Config for Runtime {
     type Block = Block;
     type RuntimeEvent = RuntimeEvent;
     type BlockHashCount = frame_support::traits::ConstU32<2400>;
-    type Version = ();
+    type Version = Version;
     type PalletInfo = PalletInfo;
     type AccountData = pallet_balances::AccountData<Balance>;
     type OnNewAccount = ();
     type OnKilledAccount = ();
     type SystemWeightInfo = ();
-    type SS58Prefix = frame_support::traits::ConstU16<42>;
+    type SS58Prefix = frame_support::traits::ConstU16<SS58_PREFIX>;
     type OnSetCode = ();
     type MaxConsumers = frame_support::traits::ConstU32<16>;
     type RuntimeTask = ();
@@ -114,7 +181,7 @@ impl frame_system::Config for Runtime {
 /// Timestamp Pallet 配置
 impl pallet_timestamp::Config for Runtime {
     type Moment = u64;
-    type OnTimestampSet = ();
+    type OnTimestampSet = Difficulty;
     type MinimumPeriod = frame_support::traits::ConstU64<{ MILLI_SECS_PER_BLOCK / 2 }>;
     type WeightInfo = ();
 }
@@ -130,21 +197,99 @@ impl pallet_balances::Config for Runtime {
     type MaxLocks = frame_support::traits::ConstU32<50>;
     type MaxReserves = frame_support::traits::ConstU32<50>;
     type ReserveIdentifier = [u8; 8];
-    type FreezeIdentifier = ();
-    type MaxFreezes = ();
+    // 未成熟的区块奖励以 pallet_rewards::FreezeReason::CoinbaseMaturity 冻结
+    type FreezeIdentifier = RuntimeFreezeReason;
+    type MaxFreezes = frame_support::traits::VariantCountOf<RuntimeFreezeReason>;
     type RuntimeHoldReason = ();
-    type RuntimeFreezeReason = ();
+    type RuntimeFreezeReason = RuntimeFreezeReason;
     type DoneSlashHandler = ();
 }
 
+/// 权重到手续费的换算：`ExtrinsicBaseWeight` 对应 `EXTRINSIC_BASE_FEE`，按权重线性增长
+pub struct WeightToFee;
+impl WeightToFeePolynomial for WeightToFee {
+    type Balance = Balance;
+    fn polynomial() -> WeightToFeeCoefficients<Self::Balance> {
+        let p = EXTRINSIC_BASE_FEE;
+        let q = Balance::from(ExtrinsicBaseWeight::get().ref_time());
+        [WeightToFeeCoefficient {
+            degree: 1,
+            negative: false,
+            coeff_frac: Perbill::from_rational(p % q, q),
+            coeff_integer: p / q,
+        }]
+        .into_iter()
+        .collect()
+    }
+}
+
+frame_support::parameter_types! {
+    /// 基础费率的目标占用：普通交易权重上限的 50%
+    pub const TargetBlockFullness: Perquintill = Perquintill::from_percent(50);
+    /// 基础费率不低于 WeightToFee 的标定值
+    pub MinimumMultiplier: Multiplier = Multiplier::saturating_from_integer(1);
+    pub MaximumMultiplier: Multiplier = Multiplier::saturating_from_integer(1_000);
+}
+
+/// EIP-1559 式基础费率调整
+///
+/// 基础费率即 `TransactionPayment::NextFeeMultiplier`，乘在权重费上，在每个区块结束时更新：
+/// 普通交易权重高于 `TargetBlockFullness` 时上调，低于时下调，
+/// 变化量为 `费率 × |实际 - 目标| / 目标 / 8`，并限制在 `MinimumMultiplier..=MaximumMultiplier`
+pub struct BaseFeeUpdate;
+impl sp_runtime::traits::Convert<Multiplier, Multiplier> for BaseFeeUpdate {
+    fn convert(previous: Multiplier) -> Multiplier {
+        let weights = RuntimeBlockWeights::get();
+        let normal = weights
+            .get(DispatchClass::Normal)
+            .max_total
+            .unwrap_or(weights.max_block);
+        let target = TargetBlockFullness::get() * normal.ref_time();
+        if target == 0 {
+            return previous;
+        }
+        let used = System::block_weight().get(DispatchClass::Normal).ref_time();
+
+        let delta = previous.saturating_mul(Multiplier::saturating_from_rational(
+            used.abs_diff(target),
+            target.saturating_mul(BASE_FEE_MAX_CHANGE_DENOMINATOR),
+        ));
+        let next = if used > target {
+            previous.saturating_add(delta)
+        } else {
+            previous.saturating_sub(delta)
+        };
+        next.clamp(MinimumMultiplier::get(), MaximumMultiplier::get())
+    }
+}
+impl pallet_transaction_payment::MultiplierUpdate for BaseFeeUpdate {
+    fn min() -> Multiplier {
+        MinimumMultiplier::get()
+    }
+    fn max() -> Multiplier {
+        MaximumMultiplier::get()
+    }
+    fn target() -> Perquintill {
+        TargetBlockFullness::get()
+    }
+    fn variability() -> Multiplier {
+        Multiplier::saturating_from_rational(1, BASE_FEE_MAX_CHANGE_DENOMINATOR)
+    }
+}
+
 /// TransactionPayment Pallet 配置
 impl pallet_transaction_payment::Config for Runtime {
     type RuntimeEvent = RuntimeEvent;
-    type OnChargeTransaction = pallet_transaction_payment::FungibleAdapter<Balances, ()>;
+    // 基础手续费销毁，小费交给区块作者（见 pallet_rewards::DealWithFees）
+    type OnChargeTransaction =
+        pallet_transaction_payment::FungibleAdapter<Balances, pallet_rewards::DealWithFees<Runtime>>;
     type OperationalFeeMultiplier = frame_support::traits::ConstU8<5>;
-    type WeightToFee = frame_support::weights::IdentityFee<Balance>;
-    type LengthToFee = frame_support::weights::IdentityFee<Balance>;
-    type FeeMultiplierUpdate = ();
+    type WeightToFee = WeightToFee;
+    type LengthToFee = frame_support::weights::ConstantMultiplier<
+        Balance,
+        frame_support::traits::ConstU128<TRANSACTION_BYTE_FEE>,
+    >;
+    type FeeMultiplierUpdate = BaseFeeUpdate;
     type WeightInfo = pallet_transaction_payment::weights::SubstrateWeight<Runtime>;
 }
 
@@ -155,6 +300,66 @@ impl pallet_sudo::Config for Runtime {
     type WeightInfo = ();
 }
 
+/// PoW 预运行时摘要：区块作者与可选的奖励分配
+pub type Coinbase = pallet_rewards::Coinbase<AccountId>;
+
+pub use pallet_rewards::MAX_PAYEES;
+
+/// 从 PoW 预运行时摘要中解码区块作者与奖励分配
+///
+/// 矿工将 SCALE 编码的 [`Coinbase`] 作为 `POW_ENGINE_ID` 预运行时摘要写入区块头
+pub struct PowAuthor;
+
+impl FindAuthor<Coinbase> for PowAuthor {
+    fn find_author<'a, I>(digests: I) -> Option<Coinbase>
+    where
+        I: 'a + IntoIterator<Item = (ConsensusEngineId, &'a [u8])>,
+    {
+        digests.into_iter().find_map(|(id, data)| {
+            if id == sp_consensus_pow::POW_ENGINE_ID {
+                Coinbase::from_digest(data)
+            } else {
+                None
+            }
+        })
+    }
+}
+
+frame_support::parameter_types! {
+    /// 配置国库后区块作者获得的手续费比例
+    pub const AuthorFeeShare: sp_runtime::Percent = sp_runtime::Percent::from_percent(80);
+}
+
+/// Rewards Pallet 配置
+impl pallet_rewards::Config for Runtime {
+    type RuntimeEvent = RuntimeEvent;
+    type RuntimeFreezeReason = RuntimeFreezeReason;
+    type Currency = Balances;
+    type FindAuthor = PowAuthor;
+    type InitialReward = frame_support::traits::ConstU128<INITIAL_BLOCK_REWARD>;
+    type HalvingInterval = frame_support::traits::ConstU32<HALVING_INTERVAL>;
+    type MaxSupply = frame_support::traits::ConstU128<TOTAL_SUPPLY>;
+    type CoinbaseMaturity = frame_support::traits::ConstU32<COINBASE_MATURITY>;
+    // 暂不设国库，小费全部归区块作者
+    type FeeTreasury = ();
+    type AuthorFeeShare = AuthorFeeShare;
+}
+
+frame_support::parameter_types! {
+    /// 难度下限
+    pub MinDifficulty: U256 = U256::from(1000u64);
+}
+
+/// Difficulty Pallet 配置
+impl pallet_difficulty::Config for Runtime {
+    type RuntimeEvent = RuntimeEvent;
+    type TargetBlockTime = frame_support::traits::ConstU64<MILLI_SECS_PER_BLOCK>;
+    // 每 60 个区块（约 6 分钟）调整一次难度
+    type AdjustmentWindow = frame_support::traits::ConstU32<60>;
+    type MaxAdjustmentFactor = frame_support::traits::ConstU32<4>;
+    type MinDifficulty = MinDifficulty;
+}
+
 // BitNice 运行时 API 实现，提供区块链核心接口
 sp_api::impl_runtime_apis! {
     impl sp_api::Core<Block> for Runtime {
@@ -245,9 +450,9 @@ sp_api::impl_runtime_apis! {
     }
 
     // PoW 难度接口
-    impl sp_consensus_pow::DifficultyApi<Block, u128> for Runtime {
-        fn difficulty() -> u128 {
-            1000000
+    impl sp_consensus_pow::DifficultyApi<Block, U256> for Runtime {
+        fn difficulty() -> U256 {
+            Difficulty::difficulty()
         }
     }
 
@@ -276,12 +481,37 @@ sp_api::impl_runtime_apis! {
         }
     }
 
+    // 区块奖励查询接口
+    impl pallet_rewards_runtime_api::RewardsApi<Block, AccountId, Balance> for Runtime {
+        fn immature_balance(who: AccountId) -> Balance {
+            Rewards::immature_balance(&who)
+        }
+    }
+
     // System RPC API
     impl frame_system_rpc_runtime_api::AccountNonceApi<Block, AccountId, Nonce> for Runtime {
         fn account_nonce(account: AccountId) -> Nonce {
             System::account_nonce(account)
         }
     }
+
+    // 创世配置构建接口
+    impl sp_genesis_builder::GenesisBuilder<Block> for Runtime {
+        fn build_state(config: Vec<u8>) -> sp_genesis_builder::Result {
+            frame_support::genesis_builder_helper::build_state::<RuntimeGenesisConfig>(config)
+        }
+
+        fn get_preset(id: &Option<sp_genesis_builder::PresetId>) -> Option<Vec<u8>> {
+            frame_support::genesis_builder_helper::get_preset::<RuntimeGenesisConfig>(
+                id,
+                genesis_config_presets::get_preset,
+            )
+        }
+
+        fn preset_names() -> Vec<sp_genesis_builder::PresetId> {
+            genesis_config_presets::preset_names()
+        }
+    }
 }
 
 // 实现 PoW 难度接口